@@ -1,119 +1,2586 @@
-use std::error::Error;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
 use base64::Engine;
 use base64::engine::general_purpose;
-use reqwest::Client;
+use futures_util::{Stream, StreamExt, stream};
+use reqwest::header::{CONTENT_TYPE, HeaderMap, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 use sha1::Digest;
 
-use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::amount::Amount;
+use crate::common::card::mask_card_number;
+use crate::common::enums::{Action, Language, Result as ResponseResult, Version};
+use crate::common::traits::{LiqPayRequest, LiqPayResponse, SignatureAlgorithm};
+use crate::error::LiqPayError;
+use crate::informational::archive::{ArchiveEntry, ArchiveResponse, DailyArchiveRequests};
+use crate::informational::status::{StatusRequest, StatusResponse};
+use crate::internet_acquiring::invoice::{InvoiceUnitsRequest, InvoiceUnitsResponse};
+use crate::internet_acquiring::refund::{RefundRequest, RefundResponse};
+use crate::internet_acquiring::two_step::{PaymentCompletionRequest, PaymentCompletionResponse};
+use crate::partner::create_company::{MccCodesRequest, MccCodesResponse};
+use crate::tokens::{CardTokenAction, ChangeTokenStatusRequest, ChangeTokenStatusResponse};
 
+/// Represents the endpoint of the LiqPay API. Requests of all API versions are sent to it,
+/// the version is taken from the `version` field of the request `data`.
 const CLIENT_URL: &'static str = "https://www.liqpay.ua/api/request";
 const DATA: &'static str = "data";
 const SIGNATURE: &'static str = "signature";
+const BODY_SNIPPET_LENGTH: usize = 200;
+const REDACTED: &str = "***";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
 
-type BoxError = Box<dyn Error + Send + Sync>;
 type FormData = [(&'static str, String); 2];
 
 fn build_signature(private_key: &str, body: &str) -> String {
     format!("{}{}{}", private_key, body, private_key)
 }
 
-fn build_form_data<Req, Resp, Alg>(private_key: &str, request: Req) -> Result<FormData, BoxError>
+fn sign<Alg: Digest>(private_key: &str, data: &str) -> String {
+    let signature = build_signature(private_key, data);
+    let hashed_signature = Alg::digest(signature.as_bytes());
+
+    general_purpose::STANDARD.encode(hashed_signature)
+}
+
+/// Represents a request together with the API version, which is determined by the signature algorithm.
+#[derive(Serialize)]
+struct SignedRequest<'a, Req> {
+    version: Version,
+    #[serde(flatten)]
+    request: &'a Req,
+}
+
+/// Encodes and signs a request. The fields of the request, which are not set, are filled from `config`.
+fn build_form_data<Req, Resp, Alg>(
+    private_key: &str,
+    mut request: Req,
+    config: &ClientConfig,
+) -> Result<FormData, LiqPayError>
 where
     Req: LiqPayRequest<Resp, Alg> + Serialize,
     Resp: LiqPayResponse + DeserializeOwned,
-    Alg: Digest,
+    Alg: SignatureAlgorithm,
 {
+    request.apply_defaults(config);
+
+    let request = SignedRequest {
+        version: Alg::VERSION,
+        request: &request,
+    };
     let serialized_request = serde_json::to_string(&request)?;
     let encoded_request = general_purpose::STANDARD.encode(serialized_request.as_bytes());
-
-    let signature = build_signature(private_key, &encoded_request);
-    let hashed_signature = Alg::digest(signature.as_bytes());
-    let encoded_signature = general_purpose::STANDARD.encode(hashed_signature);
+    let encoded_signature = sign::<Alg>(private_key, &encoded_request);
 
     let form_data = [(DATA, encoded_request), (SIGNATURE, encoded_signature)];
 
     Ok(form_data)
 }
 
-/// A wrapper around reqwest's `Client` to send requests to LiqPay.
-pub struct LiqPayClient {
-    client: Client,
-    private_key: String,
+/// Represents the parameters, which the LiqPay Checkout JS SDK is initialized with.
+/// Serializes to `{"data": ..., "signature": ...}`, so a backend can return it to a browser as JSON.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckoutParams {
+    /// Represents the base64-encoded request.
+    pub data: String,
+    /// Represents the signature of the request.
+    pub signature: String,
+}
+
+impl CheckoutParams {
+    /// Constructs new checkout parameters by encoding and signing a request.
+    /// The fields of the request, which are not set, are filled from `config` as `LiqPayClient` does.
+    pub fn new<Req, Resp, Alg>(
+        request: Req,
+        private_key: &str,
+        config: &ClientConfig,
+    ) -> Result<Self, LiqPayError>
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        let [(_, data), (_, signature)] = build_form_data(private_key, request, config)?;
+
+        Ok(Self { data, signature })
+    }
+}
+
+/// Encodes a request into the `application/x-www-form-urlencoded` body, which is sent to LiqPay,
+/// i.e. `data=<base64-encoded request>&signature=<base64-encoded signature>`.
+/// The fields of the request, which are not set, are filled from `config` as `LiqPayClient` does.
+pub fn encode_form_body<Req, Resp, Alg>(
+    request: Req,
+    private_key: &str,
+    config: &ClientConfig,
+) -> Result<String, LiqPayError>
+where
+    Req: LiqPayRequest<Resp, Alg> + Serialize,
+    Resp: LiqPayResponse + DeserializeOwned,
+    Alg: SignatureAlgorithm,
+{
+    let form_data = build_form_data(private_key, request, config)?;
+
+    Ok(encode_form_data(&form_data))
+}
+
+fn encode_form_data(form_data: &FormData) -> String {
+    form_data
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, url_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn url_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Fails with `LiqPayError::RateLimited` if LiqPay has responded with `429 Too Many Requests`.
+/// Only the delay-seconds form of the `Retry-After` header is recognized.
+fn check_rate_limit(status: StatusCode, headers: &HeaderMap) -> Result<(), LiqPayError> {
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return Ok(());
+    }
+
+    let retry_after = headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs);
+
+    Err(LiqPayError::RateLimited { retry_after })
+}
+
+/// Indicates whether a response holds a LiqPay reply, i.e. it is successful and is not an HTML page,
+/// such as the one served during maintenance.
+fn is_deserializable(status: StatusCode, headers: &HeaderMap) -> bool {
+    let is_html = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim_start().starts_with("text/html"));
+
+    status.is_success() && !is_html
+}
+
+fn unavailable(status: StatusCode, body: &str) -> LiqPayError {
+    LiqPayError::Unavailable {
+        status: status.as_u16(),
+        body_snippet: body.chars().take(BODY_SNIPPET_LENGTH).collect(),
+    }
+}
+
+/// Creates a span describing a request. Only the action and the order identifier are recorded,
+/// so that card details, the encoded data and the signature never reach the subscriber.
+#[cfg(feature = "tracing")]
+fn request_span(action: Option<&Action>, order_id: Option<&str>) -> tracing::Span {
+    tracing::info_span!(
+        "liqpay_request",
+        action = action.map(Action::as_str).unwrap_or_default(),
+        order_id = order_id.unwrap_or_default(),
+    )
+}
+
+#[cfg(feature = "tracing")]
+fn trace_response(status: u16, started_at: Instant) {
+    tracing::info!(
+        status,
+        latency_ms = started_at.elapsed().as_millis() as u64,
+        "received a response from LiqPay"
+    );
+}
+
+/// Verifies the signature of a callback, which LiqPay sends to the `server_url`.
+/// `Alg` must match the algorithm used to sign the request that initiated the operation.
+pub fn verify_callback<Alg: Digest>(private_key: &str, data: &str, signature: &str) -> bool {
+    expected_callback_signature::<Alg>(private_key, data) == signature
+}
+
+/// Returns the signature, which LiqPay sends along with the callback `data` to the `server_url`.
+/// Allows fabricating callbacks, which pass `verify_callback`, for example in tests.
+pub fn expected_callback_signature<Alg: Digest>(private_key: &str, data: &str) -> String {
+    sign::<Alg>(private_key, data)
+}
+
+/// Decodes the base64-encoded `data` of a callback, which LiqPay sends to the `server_url`.
+pub fn decode_callback<T: DeserializeOwned>(data: &str) -> Result<T, LiqPayError> {
+    let decoded_data = general_purpose::STANDARD.decode(data)?;
+    let deserialized_data = serde_json::from_slice(&decoded_data)?;
+
+    Ok(deserialized_data)
+}
+
+/// Decodes the base64-encoded `data` of a request and hides the card details in it, so that the request
+/// can be logged safely. Card numbers are masked, CVV codes and card tokens are replaced entirely.
+/// Returns the redacted JSON, which is meant for logs only and must never be sent to LiqPay.
+pub fn redact_data_for_log(data: &str) -> String {
+    let decoded_request = general_purpose::STANDARD
+        .decode(data)
+        .ok()
+        .and_then(|decoded| serde_json::from_slice::<Value>(&decoded).ok());
+
+    let Some(mut request) = decoded_request else {
+        return String::from("<undecodable data>");
+    };
+
+    if let Value::Object(fields) = &mut request {
+        for (name, value) in fields.iter_mut() {
+            match name.as_str() {
+                "card" | "receiver_card" => {
+                    let masked_card = value.as_str().map(mask_card_number).unwrap_or_default();
+                    *value = Value::from(masked_card);
+                }
+                "card_cvv" | "card_token" | "receiver_card_token" => {
+                    *value = Value::from(REDACTED);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    request.to_string()
+}
+
+impl LiqPayResponse for Value {}
+
+/// Represents a request to an arbitrary LiqPay action, which is not modeled by the crate.
+/// The request is signed with `Alg`, which also determines the API version of the request.
+/// The response is returned as raw JSON.
+#[derive(Debug, Serialize)]
+pub struct RawRequest<Alg> {
+    public_key: String,
+    action: String,
+    #[serde(flatten)]
+    params: Map<String, Value>,
+    #[serde(skip)]
+    algorithm: PhantomData<Alg>,
+}
+
+impl<Alg: SignatureAlgorithm> LiqPayRequest<Value, Alg> for RawRequest<Alg> {
+    fn action(&self) -> Option<Action> {
+        serde_json::from_value(Value::String(self.action.clone())).ok()
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        self.params.get("order_id").and_then(Value::as_str)
+    }
+}
+
+impl<Alg: SignatureAlgorithm> RawRequest<Alg> {
+    /// Constructs a new request to the given action.
+    pub fn new(public_key: impl Into<String>, action: impl Into<String>) -> Self {
+        Self {
+            public_key: public_key.into(),
+            action: action.into(),
+            params: Map::new(),
+            algorithm: PhantomData,
+        }
+    }
+
+    /// Constructs a new request to the given action from a JSON object of parameters.
+    /// `public_key` is taken from the object if it holds one, otherwise the given public key is used.
+    /// A `version` held by the object must match the version of `Alg`. An `action` held by the object is ignored.
+    pub fn from_value(
+        public_key: impl Into<String>,
+        action: impl Into<String>,
+        value: Value,
+    ) -> Result<Self, LiqPayError> {
+        let Value::Object(mut params) = value else {
+            return Err(LiqPayError::Validation(
+                "the parameters of a raw request must be a JSON object".to_owned(),
+            ));
+        };
+
+        params.remove("action");
+
+        let public_key = match params.remove("public_key") {
+            Some(Value::String(public_key)) => public_key,
+            Some(_) => {
+                return Err(LiqPayError::Validation(
+                    "`public_key` must be a string".to_owned(),
+                ));
+            }
+            None => public_key.into(),
+        };
+
+        if let Some(version) = params.remove("version") {
+            let version: Version = serde_json::from_value(version)?;
+
+            if version != Alg::VERSION {
+                return Err(LiqPayError::Validation(format!(
+                    "`version` {} does not match the signature algorithm, which requires version {}",
+                    version.as_u8(),
+                    Alg::VERSION.as_u8()
+                )));
+            }
+        }
+
+        Ok(Self {
+            public_key,
+            action: action.into(),
+            params,
+            algorithm: PhantomData,
+        })
+    }
+
+    /// Sets a parameter of the request.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the parameters of the request.
+    pub fn params(mut self, params: Map<String, Value>) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+/// Represents a secret value, e.g. a private key, which is never printed.
+/// The value is zeroed out on drop when the `zeroize` feature is enabled.
+pub struct Secret(String);
+
+impl Secret {
+    /// Constructs a new secret value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Exposes the underlying secret value.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([REDACTED])")
+    }
+}
+
+#[cfg(feature = "zeroize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zeroize")))]
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+/// Represents the configuration of a LiqPay client.
+/// By default, a request is sent once and is not retried, responses are not compressed,
+/// requests are not sent to the sandbox and no default language is set.
+/// Once retries are enabled, only requests of idempotent actions, such as `status`, are retried.
+/// `send_all` sends at most 4 requests at a time.
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    max_retries: u32,
+    retry_delay: Duration,
+    decompression: bool,
+    sandbox: bool,
+    default_language: Option<Language>,
+    retry_non_idempotent: bool,
+    max_concurrency: usize,
+}
+
+impl ClientConfig {
+    /// Constructs a new client configuration with retries disabled.
+    pub fn new() -> Self {
+        Self {
+            max_retries: 0,
+            retry_delay: Duration::from_secs(1),
+            decompression: false,
+            sandbox: false,
+            default_language: None,
+            retry_non_idempotent: false,
+            max_concurrency: 4,
+        }
+    }
+
+    /// Sets the number of times a request is retried after a connection failure, a timeout
+    /// or `429 Too Many Requests`.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the maximum number of requests, which `send_all` sends at a time. Must be at least 1.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Sets whether requests of actions, which are not idempotent (e.g. `pay`), are retried as well.
+    /// A retried payment is deduplicated by LiqPay only if its `order_id` is set, so enabling this
+    /// without unique order identifiers may charge a customer twice.
+    pub fn retry_non_idempotent(mut self, retry_non_idempotent: bool) -> Self {
+        self.retry_non_idempotent = retry_non_idempotent;
+        self
+    }
+
+    /// Sets the delay between retries. The `Retry-After` header takes precedence, if LiqPay has sent one.
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Sets whether gzip and deflate compressed responses are accepted and transparently decoded.
+    /// Reduces the bandwidth used by large reports.
+    pub fn decompression(mut self, decompression: bool) -> Self {
+        self.decompression = decompression;
+        self
+    }
+
+    /// Sets whether requests, which support the sandbox environment (i.e. P2P transfers), are sent to it
+    /// for testing purposes, as if `sandbox` has been set on the request itself.
+    pub fn sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Sets the language, which is sent with every request that supports a language
+    /// and does not set one explicitly.
+    pub fn default_language(mut self, language: Language) -> Self {
+        self.default_language = Some(language);
+        self
+    }
+
+    /// Returns whether requests, which support the sandbox environment, are sent to it.
+    pub fn is_sandbox(&self) -> bool {
+        self.sandbox
+    }
+
+    /// Returns the language, which is sent with requests that do not set a language explicitly.
+    pub fn language(&self) -> Option<&Language> {
+        self.default_language.as_ref()
+    }
+
+    /// Returns the delay before retrying a request of `action`, which has failed with `error`,
+    /// or `None` if the request must not be retried.
+    fn delay_before_retry(
+        &self,
+        action: Option<&Action>,
+        error: &LiqPayError,
+        attempt: u32,
+    ) -> Option<Duration> {
+        let is_retryable = self.retry_non_idempotent || action.is_some_and(Action::is_idempotent);
+
+        if !is_retryable || attempt >= self.max_retries {
+            return None;
+        }
+
+        match error {
+            LiqPayError::RateLimited { retry_after } => {
+                Some(retry_after.unwrap_or(self.retry_delay))
+            }
+            LiqPayError::Http(error) if error.is_connect() || error.is_timeout() => {
+                Some(self.retry_delay)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A wrapper around a `Transport`, which is reqwest's `Client` by default, to send requests to LiqPay.
+///
+/// A retried request is sent with the same `data` and `signature` as the first attempt.
+/// Only requests of idempotent actions are retried, unless `ClientConfig::retry_non_idempotent` is set.
+/// LiqPay deduplicates payments by `order_id`, so the order identifier serves as the idempotency key.
+#[derive(Debug)]
+pub struct LiqPayClient<T: Transport = HttpTransport> {
+    transport: T,
+    private_key: Secret,
+    config: ClientConfig,
+    last_request_data: Mutex<Option<String>>,
 }
 
 impl LiqPayClient {
     /// Constructs a new LiqPay client instance.
-    pub fn new(private_key: impl Into<String>) -> Self {
+    /// Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+    pub fn new(private_key: impl Into<String>) -> Result<Self, LiqPayError> {
+        Self::with_config(private_key, ClientConfig::new())
+    }
+
+    /// Constructs a new LiqPay client instance, which sends requests supporting the sandbox environment to it.
+    /// Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+    pub fn sandbox(private_key: impl Into<String>) -> Result<Self, LiqPayError> {
+        Self::with_config(private_key, ClientConfig::new().sandbox(true))
+    }
+
+    /// Constructs a new LiqPay client instance with the given configuration.
+    /// Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+    pub fn with_config(
+        private_key: impl Into<String>,
+        config: ClientConfig,
+    ) -> Result<Self, LiqPayError> {
+        let transport = HttpTransport::new(&config)?;
+
+        Ok(Self::with_transport(private_key, config, transport))
+    }
+}
+
+impl<T: Transport> LiqPayClient<T> {
+    /// Constructs a new LiqPay client instance, which sends requests through the given transport.
+    pub fn with_transport(
+        private_key: impl Into<String>,
+        config: ClientConfig,
+        transport: T,
+    ) -> Self {
         Self {
-            client: Client::new(),
-            private_key: private_key.into(),
+            transport,
+            private_key: Secret::new(private_key),
+            config,
+            last_request_data: Mutex::new(None),
         }
     }
 
-    /// Sends an asynchronous HTTP request to the LiqPay API using the underlying `reqwest::Client`.
-    pub async fn send<Req, Resp, Alg>(&self, request: Req) -> Result<Resp, BoxError>
+    #[cfg(test)]
+    pub(crate) fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Returns the base64-encoded `data` of the last request sent by the client.
+    pub fn last_request_data(&self) -> Option<String> {
+        lock(&self.last_request_data).clone()
+    }
+
+    /// Returns the base64-encoded `data` and the `signature`, which `send` would send for a request,
+    /// without sending it.
+    pub fn dry_run<Req, Resp, Alg>(&self, request: Req) -> Result<(String, String), LiqPayError>
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        let [(_, data), (_, signature)] =
+            build_form_data(self.private_key.expose(), request, &self.config)?;
+
+        Ok((data, signature))
+    }
+
+    /// Sends an asynchronous HTTP request to the LiqPay API using the underlying transport.
+    /// The request is retried according to the client configuration.
+    pub async fn send<Req, Resp, Alg>(&self, request: Req) -> Result<Resp, LiqPayError>
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        self.exchange::<Req, Resp, Alg, Resp>(request).await
+    }
+
+    /// Sends several independent requests concurrently, at most `ClientConfig::max_concurrency`
+    /// at a time. The result of each request is returned in the order of `requests`.
+    pub async fn send_all<Req, Resp, Alg>(
+        &self,
+        requests: impl IntoIterator<Item = Req>,
+    ) -> Vec<Result<Resp, LiqPayError>>
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        stream::iter(requests)
+            .map(|request| self.send::<Req, Resp, Alg>(request))
+            .buffered(self.config.max_concurrency)
+            .collect()
+            .await
+    }
+
+    /// Sends a request like `send` and returns the parsed response along with the untouched JSON body.
+    pub async fn send_with_raw<Req, Resp, Alg>(
+        &self,
+        request: Req,
+    ) -> Result<(Resp, Value), LiqPayError>
     where
         Req: LiqPayRequest<Resp, Alg> + Serialize,
         Resp: LiqPayResponse + DeserializeOwned,
-        Alg: Digest,
+        Alg: SignatureAlgorithm,
     {
-        let form_data = build_form_data(&self.private_key, request)?;
+        let raw_response: Value = self.exchange::<Req, Resp, Alg, Value>(request).await?;
+        let response = serde_json::from_value(raw_response.clone())?;
+
+        Ok((response, raw_response))
+    }
+
+    async fn exchange<Req, Resp, Alg, Body>(&self, request: Req) -> Result<Body, LiqPayError>
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+        Body: DeserializeOwned,
+    {
+        let action = LiqPayRequest::<Resp, Alg>::action(&request);
+
+        #[cfg(feature = "tracing")]
+        let span = request_span(
+            action.as_ref(),
+            LiqPayRequest::<Resp, Alg>::order_id(&request),
+        );
+
+        let future = async move {
+            let (data, signature) = self.dry_run::<Req, Resp, Alg>(request)?;
+            *lock(&self.last_request_data) = Some(data.clone());
+
+            let body = encode_form_data(&[(DATA, data), (SIGNATURE, signature)]);
+
+            let mut attempt = 0;
+
+            loop {
+                match self.post(&body).await {
+                    Err(error) => {
+                        match self
+                            .config
+                            .delay_before_retry(action.as_ref(), &error, attempt)
+                        {
+                            Some(delay) => {
+                                tokio::time::sleep(delay).await;
+                                attempt += 1;
+                            }
+                            None => return Err(error),
+                        }
+                    }
+                    response => return response,
+                }
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        let future = tracing::Instrument::instrument(future, span);
+
+        future.await
+    }
+
+    async fn post<Resp: DeserializeOwned>(&self, body: &str) -> Result<Resp, LiqPayError> {
+        #[cfg(feature = "tracing")]
+        let started_at = Instant::now();
+
+        let response = self.transport.execute(CLIENT_URL, body).await?;
+
+        #[cfg(feature = "tracing")]
+        trace_response(response.status.as_u16(), started_at);
+
+        parse_response(response)
+    }
+}
+
+/// Interprets a response received by a transport, e.g. turns `429 Too Many Requests`
+/// into `LiqPayError::RateLimited`, and deserializes its body otherwise.
+fn parse_response<Resp: DeserializeOwned>(
+    response: TransportResponse,
+) -> Result<Resp, LiqPayError> {
+    check_rate_limit(response.status, &response.headers)?;
+
+    if !is_deserializable(response.status, &response.headers) {
+        return Err(unavailable(response.status, &response.body));
+    }
+
+    let deserialized_response = serde_json::from_str(&response.body)?;
+
+    Ok(deserialized_response)
+}
+
+/// Represents the HTTP layer, through which `LiqPayClient` sends requests to LiqPay.
+pub trait Transport {
+    /// Posts the `application/x-www-form-urlencoded` body of a request to `url` and returns the response as is.
+    /// An error is returned only if no response has been received, as the response is interpreted
+    /// (e.g. `429 Too Many Requests` is turned into `LiqPayError::RateLimited`) by `LiqPayClient`.
+    fn execute(
+        &self,
+        url: &str,
+        body: &str,
+    ) -> impl Future<Output = Result<TransportResponse, LiqPayError>> + Send;
+}
+
+/// Represents a response received by a `Transport`.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    /// Represents the HTTP status of the response.
+    pub status: StatusCode,
+    /// Represents the headers of the response.
+    pub headers: HeaderMap,
+    /// Represents the body of the response.
+    pub body: String,
+}
 
-        let deserialized_response = self
+/// Represents the default transport, which sends requests with `reqwest::Client`.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: Client,
+}
+
+impl HttpTransport {
+    /// Constructs a new transport with the HTTP settings of the given configuration.
+    /// Fails with `LiqPayError::Http` if the HTTP client cannot be initialized, e.g. the TLS backend is unavailable.
+    pub fn new(config: &ClientConfig) -> Result<Self, LiqPayError> {
+        let client = Client::builder()
+            .gzip(config.decompression)
+            .deflate(config.decompression)
+            .build()?;
+
+        Ok(Self { client })
+    }
+}
+
+impl Transport for HttpTransport {
+    async fn execute(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+        let response = self
             .client
-            .post(CLIENT_URL)
-            .form(&form_data)
+            .post(url)
+            .header(CONTENT_TYPE, FORM_CONTENT_TYPE)
+            .body(body.to_owned())
             .send()
-            .await?
-            .json()
             .await?;
 
-        Ok(deserialized_response)
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+
+        Ok(TransportResponse {
+            status,
+            headers,
+            body,
+        })
     }
 }
 
-#[cfg(feature = "blocking")]
-#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
-pub mod blocking {
-    use super::*;
+/// Locks the mutex, recovering the value if another thread has panicked while holding the lock.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
-    use reqwest::blocking::Client;
+/// Sends status requests for an order every `interval` while its status is pending, i.e. until
+/// the status is final, retryable, requires an action or is an active subscription.
+/// If `timeout` elapses first, the last response is returned, so its status is still pending.
+pub async fn poll_status<T: Transport>(
+    client: &LiqPayClient<T>,
+    public_key: impl Into<String>,
+    order_id: impl Into<String>,
+    interval: Duration,
+    timeout: Duration,
+) -> Result<StatusResponse, LiqPayError> {
+    let public_key = public_key.into();
+    let order_id = order_id.into();
+    let started_at = Instant::now();
 
-    /// A wrapper around reqwest's blocking `Client` to send requests to LiqPay.
-    pub struct BlockLiqPayClient {
-        client: Client,
-        private_key: String,
+    loop {
+        let request = StatusRequest::new(public_key.clone(), order_id.clone());
+        let response: StatusResponse = client.send(request).await?;
+
+        if !response.status.is_pending() || started_at.elapsed() + interval > timeout {
+            return Ok(response);
+        }
+
+        tokio::time::sleep(interval).await;
     }
+}
 
-    impl BlockLiqPayClient {
-        /// Constructs a new blocking LiqPay client instance.
-        pub fn new(private_key: impl Into<String>) -> Self {
-            Self {
-                client: Client::new(),
-                private_key: private_key.into(),
+/// Captures the funds blocked by a funds blocking request for an order. The amount may be less
+/// than the blocked one, in which case the rest of the funds is released.
+pub async fn capture<T: Transport>(
+    client: &LiqPayClient<T>,
+    public_key: impl Into<String>,
+    order_id: impl Into<String>,
+    amount: impl Into<Amount>,
+) -> Result<PaymentCompletionResponse, LiqPayError> {
+    let request = PaymentCompletionRequest::new(public_key, amount, order_id.into()).build()?;
+
+    client.send(request).await
+}
+
+/// Releases the funds blocked by a funds blocking request for an order, which has not been captured.
+pub async fn reverse<T: Transport>(
+    client: &LiqPayClient<T>,
+    public_key: impl Into<String>,
+    order_id: impl Into<String>,
+    amount: impl Into<Amount>,
+) -> Result<RefundResponse, LiqPayError> {
+    let request = RefundRequest::new(public_key, order_id.into(), amount).build()?;
+
+    client.send(request).await
+}
+
+/// Changes the statuses of several card tokens. The requests are sent concurrently with `send_all`,
+/// and the result of each one is returned in the order of `changes`.
+pub async fn change_token_statuses<T: Transport>(
+    client: &LiqPayClient<T>,
+    public_key: impl Into<String>,
+    changes: Vec<(String, CardTokenAction)>,
+) -> Vec<Result<ChangeTokenStatusResponse, LiqPayError>> {
+    let public_key = public_key.into();
+    let requests = changes.into_iter().map(|(card_token, action)| {
+        ChangeTokenStatusRequest::new(public_key.clone(), card_token, action)
+    });
+
+    client.send_all(requests).await
+}
+
+/// Streams the archive of received payments made between two dates, requesting it one day at a time.
+/// The dates are timestamps in milliseconds, and both of them are included in the range.
+/// The next day is requested only once the entries of the previous one are consumed,
+/// and the stream ends after the first error.
+pub fn archive_entries<T: Transport>(
+    client: &LiqPayClient<T>,
+    public_key: impl Into<String>,
+    date_from: i64,
+    date_to: i64,
+) -> impl Stream<Item = Result<ArchiveEntry, LiqPayError>> + '_ {
+    let requests = DailyArchiveRequests::new(public_key, date_from, date_to);
+
+    stream::unfold(
+        (Some(requests), Vec::new().into_iter()),
+        move |(mut requests, mut entries)| async move {
+            loop {
+                if let Some(entry) = entries.next() {
+                    return Some((Ok(entry), (requests, entries)));
+                }
+
+                let request = requests.as_mut()?.next()?;
+
+                match client.send::<_, ArchiveResponse, _>(request).await {
+                    Ok(response) => entries = response.data.unwrap_or_default().into_iter(),
+                    Err(error) => return Some((Err(error), (None, entries))),
+                }
             }
+        },
+    )
+}
+
+/// Memoizes the reference lists of LiqPay, i.e. invoice units and MCC codes, per language.
+/// A cached list is requested again once `ttl` has elapsed since it was fetched.
+/// Only successful responses are cached.
+#[derive(Debug)]
+pub struct ReferenceCache<T: Transport = HttpTransport> {
+    client: LiqPayClient<T>,
+    public_key: String,
+    ttl: Duration,
+    invoice_units: Mutex<HashMap<&'static str, Cached<InvoiceUnitsResponse>>>,
+    mcc_codes: Mutex<HashMap<&'static str, Cached<MccCodesResponse>>>,
+}
+
+#[derive(Debug)]
+struct Cached<V> {
+    fetched_at: Instant,
+    value: V,
+}
+
+impl<T: Transport> ReferenceCache<T> {
+    /// Constructs a new cache, which requests the reference lists with the given client.
+    pub fn new(client: LiqPayClient<T>, public_key: impl Into<String>, ttl: Duration) -> Self {
+        Self {
+            client,
+            public_key: public_key.into(),
+            ttl,
+            invoice_units: Mutex::new(HashMap::new()),
+            mcc_codes: Mutex::new(HashMap::new()),
         }
+    }
 
-        /// Sends a blocking HTTP request to the LiqPay API using the underlying `reqwest::blocking::Client`.
-        pub fn send<Req, Resp, Alg>(&self, request: Req) -> Result<Resp, BoxError>
-        where
-            Req: LiqPayRequest<Resp, Alg> + Serialize,
-            Resp: LiqPayResponse + DeserializeOwned,
-            Alg: Digest,
-        {
-            let form_data = build_form_data(&self.private_key, request)?;
+    /// Returns the invoice units in the given language, requesting them if they are not cached.
+    pub async fn get_invoice_units(
+        &self,
+        language: Language,
+    ) -> Result<InvoiceUnitsResponse, LiqPayError> {
+        let key = language.as_str();
 
-            let deserialized_response = self
-                .client
-                .post(CLIENT_URL)
-                .form(&form_data)
-                .send()?
-                .json()?;
+        if let Some(response) = self.cached(&self.invoice_units, key) {
+            return Ok(response);
+        }
 
-            Ok(deserialized_response)
+        let request = InvoiceUnitsRequest::by_language(self.public_key.clone(), language);
+        let response: InvoiceUnitsResponse = self.client.send(request).await?;
+
+        if response.result == ResponseResult::Ok {
+            self.store(&self.invoice_units, key, response.clone());
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the MCC codes in the given language, requesting them if they are not cached.
+    pub async fn get_mcc_codes(&self, language: Language) -> Result<MccCodesResponse, LiqPayError> {
+        let key = language.as_str();
+
+        if let Some(response) = self.cached(&self.mcc_codes, key) {
+            return Ok(response);
         }
+
+        let request = MccCodesRequest::new(self.public_key.clone()).language(language);
+        let response: MccCodesResponse = self.client.send(request).await?;
+
+        if response.result == ResponseResult::Ok {
+            self.store(&self.mcc_codes, key, response.clone());
+        }
+
+        Ok(response)
+    }
+
+    /// Drops every cached list, so that the next lookups are requested from LiqPay.
+    pub fn clear(&self) {
+        lock(&self.invoice_units).clear();
+        lock(&self.mcc_codes).clear();
+    }
+
+    fn cached<V: Clone>(
+        &self,
+        entries: &Mutex<HashMap<&'static str, Cached<V>>>,
+        key: &'static str,
+    ) -> Option<V> {
+        lock(entries)
+            .get(key)
+            .filter(|entry| entry.fetched_at.elapsed() < self.ttl)
+            .map(|entry| entry.value.clone())
+    }
+
+    fn store<V>(
+        &self,
+        entries: &Mutex<HashMap<&'static str, Cached<V>>>,
+        key: &'static str,
+        value: V,
+    ) {
+        let entry = Cached {
+            fetched_at: Instant::now(),
+            value,
+        };
+
+        lock(entries).insert(key, entry);
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking {
+    use super::*;
+
+    use std::thread;
+    use std::vec::IntoIter;
+
+    use reqwest::blocking::Client;
+
+    /// Represents the HTTP layer, through which `BlockLiqPayClient` sends requests to LiqPay.
+    pub trait BlockTransport {
+        /// Posts the `application/x-www-form-urlencoded` body of a request to `url` and returns the response as is.
+        /// An error is returned only if no response has been received, as the response is interpreted
+        /// by `BlockLiqPayClient`.
+        fn execute(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError>;
+    }
+
+    /// Represents the default blocking transport, which sends requests with `reqwest::blocking::Client`.
+    #[derive(Debug, Clone)]
+    pub struct BlockHttpTransport {
+        client: Client,
+    }
+
+    impl BlockHttpTransport {
+        /// Constructs a new blocking transport with the HTTP settings of the given configuration.
+        ///
+        /// # Errors
+        ///
+        /// Fails with `LiqPayError::Http` if the HTTP client cannot be initialized, e.g. the TLS backend is unavailable.
+        pub fn new(config: &ClientConfig) -> Result<Self, LiqPayError> {
+            let client = Client::builder()
+                .gzip(config.decompression)
+                .deflate(config.decompression)
+                .build()?;
+
+            Ok(Self { client })
+        }
+    }
+
+    impl BlockTransport for BlockHttpTransport {
+        fn execute(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+            let response = self
+                .client
+                .post(url)
+                .header(CONTENT_TYPE, FORM_CONTENT_TYPE)
+                .body(body.to_owned())
+                .send()?;
+
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.text()?;
+
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+    }
+
+    /// A wrapper around a `BlockTransport`, which is reqwest's blocking `Client` by default,
+    /// to send requests to LiqPay.
+    ///
+    /// A retried request is sent with the same `data` and `signature` as the first attempt.
+    /// Only requests of idempotent actions are retried, unless `ClientConfig::retry_non_idempotent` is set.
+    /// LiqPay deduplicates payments by `order_id`, so the order identifier serves as the idempotency key.
+    #[derive(Debug)]
+    pub struct BlockLiqPayClient<T: BlockTransport = BlockHttpTransport> {
+        transport: T,
+        private_key: Secret,
+        config: ClientConfig,
+        last_request_data: Mutex<Option<String>>,
+    }
+
+    impl BlockLiqPayClient {
+        /// Constructs a new blocking LiqPay client instance.
+        /// Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+        pub fn new(private_key: impl Into<String>) -> Result<Self, LiqPayError> {
+            Self::with_config(private_key, ClientConfig::new())
+        }
+
+        /// Constructs a new blocking LiqPay client instance, which sends requests supporting the sandbox
+        /// environment to it. Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+        pub fn sandbox(private_key: impl Into<String>) -> Result<Self, LiqPayError> {
+            Self::with_config(private_key, ClientConfig::new().sandbox(true))
+        }
+
+        /// Constructs a new blocking LiqPay client instance with the given configuration.
+        /// Fails with `LiqPayError::Http` if the underlying HTTP client cannot be initialized.
+        pub fn with_config(
+            private_key: impl Into<String>,
+            config: ClientConfig,
+        ) -> Result<Self, LiqPayError> {
+            let transport = BlockHttpTransport::new(&config)?;
+
+            Ok(Self::with_transport(private_key, config, transport))
+        }
+    }
+
+    impl<T: BlockTransport> BlockLiqPayClient<T> {
+        /// Constructs a new blocking LiqPay client instance, which sends requests through the given transport.
+        pub fn with_transport(
+            private_key: impl Into<String>,
+            config: ClientConfig,
+            transport: T,
+        ) -> Self {
+            Self {
+                transport,
+                private_key: Secret::new(private_key),
+                config,
+                last_request_data: Mutex::new(None),
+            }
+        }
+
+        /// Returns the base64-encoded `data` of the last request sent by the client.
+        pub fn last_request_data(&self) -> Option<String> {
+            lock(&self.last_request_data).clone()
+        }
+
+        /// Returns the base64-encoded `data` and the `signature`, which `send` would send for a request,
+        /// without sending it.
+        pub fn dry_run<Req, Resp, Alg>(&self, request: Req) -> Result<(String, String), LiqPayError>
+        where
+            Req: LiqPayRequest<Resp, Alg> + Serialize,
+            Resp: LiqPayResponse + DeserializeOwned,
+            Alg: SignatureAlgorithm,
+        {
+            let [(_, data), (_, signature)] =
+                build_form_data(self.private_key.expose(), request, &self.config)?;
+
+            Ok((data, signature))
+        }
+
+        /// Sends a blocking HTTP request to the LiqPay API using the underlying transport.
+        /// The request is retried according to the client configuration.
+        pub fn send<Req, Resp, Alg>(&self, request: Req) -> Result<Resp, LiqPayError>
+        where
+            Req: LiqPayRequest<Resp, Alg> + Serialize,
+            Resp: LiqPayResponse + DeserializeOwned,
+            Alg: SignatureAlgorithm,
+        {
+            self.exchange::<Req, Resp, Alg, Resp>(request)
+        }
+
+        /// Sends a request like `send` and returns the parsed response along with the untouched JSON body.
+        pub fn send_with_raw<Req, Resp, Alg>(
+            &self,
+            request: Req,
+        ) -> Result<(Resp, Value), LiqPayError>
+        where
+            Req: LiqPayRequest<Resp, Alg> + Serialize,
+            Resp: LiqPayResponse + DeserializeOwned,
+            Alg: SignatureAlgorithm,
+        {
+            let raw_response: Value = self.exchange::<Req, Resp, Alg, Value>(request)?;
+            let response = serde_json::from_value(raw_response.clone())?;
+
+            Ok((response, raw_response))
+        }
+
+        fn exchange<Req, Resp, Alg, Body>(&self, request: Req) -> Result<Body, LiqPayError>
+        where
+            Req: LiqPayRequest<Resp, Alg> + Serialize,
+            Resp: LiqPayResponse + DeserializeOwned,
+            Alg: SignatureAlgorithm,
+            Body: DeserializeOwned,
+        {
+            let action = LiqPayRequest::<Resp, Alg>::action(&request);
+
+            #[cfg(feature = "tracing")]
+            let _entered = request_span(
+                action.as_ref(),
+                LiqPayRequest::<Resp, Alg>::order_id(&request),
+            )
+            .entered();
+            let (data, signature) = self.dry_run::<Req, Resp, Alg>(request)?;
+            *lock(&self.last_request_data) = Some(data.clone());
+
+            let body = encode_form_data(&[(DATA, data), (SIGNATURE, signature)]);
+
+            let mut attempt = 0;
+
+            loop {
+                match self.post(&body) {
+                    Err(error) => {
+                        match self
+                            .config
+                            .delay_before_retry(action.as_ref(), &error, attempt)
+                        {
+                            Some(delay) => {
+                                thread::sleep(delay);
+                                attempt += 1;
+                            }
+                            None => return Err(error),
+                        }
+                    }
+                    response => return response,
+                }
+            }
+        }
+
+        fn post<Resp: DeserializeOwned>(&self, body: &str) -> Result<Resp, LiqPayError> {
+            #[cfg(feature = "tracing")]
+            let started_at = Instant::now();
+
+            let response = self.transport.execute(CLIENT_URL, body)?;
+
+            #[cfg(feature = "tracing")]
+            trace_response(response.status.as_u16(), started_at);
+
+            parse_response(response)
+        }
+    }
+
+    /// Sends status requests for an order every `interval` while its status is pending, i.e. until
+    /// the status is final, retryable, requires an action or is an active subscription.
+    /// If `timeout` elapses first, the last response is returned, so its status is still pending.
+    pub fn poll_status<T: BlockTransport>(
+        client: &BlockLiqPayClient<T>,
+        public_key: impl Into<String>,
+        order_id: impl Into<String>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<StatusResponse, LiqPayError> {
+        let public_key = public_key.into();
+        let order_id = order_id.into();
+        let started_at = Instant::now();
+
+        loop {
+            let request = StatusRequest::new(public_key.clone(), order_id.clone());
+            let response: StatusResponse = client.send(request)?;
+
+            if !response.status.is_pending() || started_at.elapsed() + interval > timeout {
+                return Ok(response);
+            }
+
+            thread::sleep(interval);
+        }
+    }
+
+    /// Captures the funds blocked by a funds blocking request for an order. The amount may be less
+    /// than the blocked one, in which case the rest of the funds is released.
+    pub fn capture<T: BlockTransport>(
+        client: &BlockLiqPayClient<T>,
+        public_key: impl Into<String>,
+        order_id: impl Into<String>,
+        amount: impl Into<Amount>,
+    ) -> Result<PaymentCompletionResponse, LiqPayError> {
+        let request = PaymentCompletionRequest::new(public_key, amount, order_id.into()).build()?;
+
+        client.send(request)
+    }
+
+    /// Releases the funds blocked by a funds blocking request for an order, which has not been captured.
+    pub fn reverse<T: BlockTransport>(
+        client: &BlockLiqPayClient<T>,
+        public_key: impl Into<String>,
+        order_id: impl Into<String>,
+        amount: impl Into<Amount>,
+    ) -> Result<RefundResponse, LiqPayError> {
+        let request = RefundRequest::new(public_key, order_id.into(), amount).build()?;
+
+        client.send(request)
+    }
+
+    /// Changes the statuses of several card tokens. The requests are sent one after another,
+    /// and the result of each one is returned in the order of `changes`.
+    pub fn change_token_statuses<T: BlockTransport>(
+        client: &BlockLiqPayClient<T>,
+        public_key: impl Into<String>,
+        changes: Vec<(String, CardTokenAction)>,
+    ) -> Vec<Result<ChangeTokenStatusResponse, LiqPayError>> {
+        let public_key = public_key.into();
+
+        changes
+            .into_iter()
+            .map(|(card_token, action)| {
+                client.send(ChangeTokenStatusRequest::new(
+                    public_key.clone(),
+                    card_token,
+                    action,
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the archive of received payments made between two dates.
+    /// The archive is requested one day at a time, as the iterator advances.
+    /// The dates are timestamps in milliseconds, and both of them are included in the range.
+    pub fn archive_entries<T: BlockTransport>(
+        client: &BlockLiqPayClient<T>,
+        public_key: impl Into<String>,
+        date_from: i64,
+        date_to: i64,
+    ) -> ArchiveEntries<'_, T> {
+        ArchiveEntries {
+            client,
+            requests: Some(DailyArchiveRequests::new(public_key, date_from, date_to)),
+            entries: Vec::new().into_iter(),
+        }
+    }
+
+    /// Represents an iterator over archive entries, which requests the archive one day at a time.
+    /// The iterator stops after the first error.
+    #[derive(Debug)]
+    pub struct ArchiveEntries<'a, T: BlockTransport = BlockHttpTransport> {
+        client: &'a BlockLiqPayClient<T>,
+        requests: Option<DailyArchiveRequests>,
+        entries: IntoIter<ArchiveEntry>,
+    }
+
+    impl<T: BlockTransport> Iterator for ArchiveEntries<'_, T> {
+        type Item = Result<ArchiveEntry, LiqPayError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                if let Some(entry) = self.entries.next() {
+                    return Some(Ok(entry));
+                }
+
+                let request = self.requests.as_mut()?.next()?;
+
+                match self.client.send::<_, ArchiveResponse, _>(request) {
+                    Ok(response) => self.entries = response.data.unwrap_or_default().into_iter(),
+                    Err(error) => {
+                        self.requests = None;
+                        return Some(Err(error));
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use reqwest::StatusCode;
+        use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+        use sha3::Sha3_256;
+
+        use super::*;
+        use crate::client::test_util::MockTransport;
+
+        #[test]
+        fn requests_are_sent_through_transport() {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, HeaderValue::from_static("0"));
+
+            let transport = MockTransport::new()
+                .respond_with(
+                    "status",
+                    TransportResponse {
+                        status: StatusCode::TOO_MANY_REQUESTS,
+                        headers,
+                        body: String::new(),
+                    },
+                )
+                .respond("status", r#"{"result":"ok","status":"success"}"#);
+            let client = BlockLiqPayClient::with_transport(
+                "private_key",
+                ClientConfig::new().max_retries(1),
+                transport,
+            );
+            let request =
+                || RawRequest::<Sha3_256>::new("public_key", "status").param("order_id", "order");
+
+            let response = client.send(request()).unwrap();
+            let (data, signature) = client.dry_run(request()).unwrap();
+
+            assert_eq!(response["status"], "success");
+            assert_eq!(
+                client.transport.bodies(),
+                vec![encode_form_data(&[(DATA, data), (SIGNATURE, signature)]); 2]
+            );
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    use base64::Engine;
+    use base64::engine::general_purpose;
+    use reqwest::StatusCode;
+    use reqwest::header::HeaderMap;
+    use serde_json::Value;
+
+    #[cfg(feature = "blocking")]
+    use super::blocking::BlockTransport;
+    use super::{DATA, Transport, TransportResponse, lock};
+    use crate::error::LiqPayError;
+
+    /// Represents a transport for tests, which replies with canned responses instead of sending requests.
+    /// The response is chosen by the `action` of a request. The responses queued for an action are returned
+    /// in order, and the last one is repeated once the others have been returned.
+    ///
+    /// # Panics
+    ///
+    /// Executing a request, for whose action no response has been queued, panics,
+    /// as it is a mistake in the test rather than an error LiqPay could return.
+    #[derive(Debug, Default)]
+    pub struct MockTransport {
+        responses: Mutex<HashMap<String, VecDeque<TransportResponse>>>,
+        requests: Mutex<Vec<Value>>,
+        bodies: Mutex<Vec<String>>,
+        urls: Mutex<Vec<String>>,
+    }
+
+    impl MockTransport {
+        /// Constructs a new transport without any canned responses.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Queues a `200 OK` response with the given body for requests with the given action, e.g. `status`.
+        pub fn respond(self, action: impl Into<String>, body: impl Into<String>) -> Self {
+            self.respond_with(
+                action,
+                TransportResponse {
+                    status: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body: body.into(),
+                },
+            )
+        }
+
+        /// Queues a response for requests with the given action, e.g. `429 Too Many Requests`
+        /// with a `Retry-After` header.
+        pub fn respond_with(self, action: impl Into<String>, response: TransportResponse) -> Self {
+            lock(&self.responses)
+                .entry(action.into())
+                .or_default()
+                .push_back(response);
+            self
+        }
+
+        /// Returns the decoded `data` of the requests received by the transport.
+        pub fn requests(&self) -> Vec<Value> {
+            lock(&self.requests).clone()
+        }
+
+        /// Returns the bodies of the requests received by the transport as they have been sent,
+        /// i.e. with the base64-encoded `data` and the `signature`.
+        pub fn bodies(&self) -> Vec<String> {
+            lock(&self.bodies).clone()
+        }
+
+        /// Returns the URLs, to which the requests received by the transport have been sent.
+        pub fn urls(&self) -> Vec<String> {
+            lock(&self.urls).clone()
+        }
+    }
+
+    impl Transport for MockTransport {
+        async fn execute(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+            self.reply(url, body)
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    impl BlockTransport for MockTransport {
+        fn execute(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+            self.reply(url, body)
+        }
+    }
+
+    impl MockTransport {
+        fn reply(&self, url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+            let request = decode_request(body)?;
+            let action = request
+                .get("action")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_owned();
+
+            lock(&self.requests).push(request);
+            lock(&self.bodies).push(body.to_owned());
+            lock(&self.urls).push(url.to_owned());
+
+            let mut responses = lock(&self.responses);
+            let queue = responses.entry(action.clone()).or_default();
+            let response = match queue.len() {
+                0 | 1 => queue.front().cloned(),
+                _ => queue.pop_front(),
+            };
+            let response = response
+                .unwrap_or_else(|| panic!("no response is queued for the `{}` action", action));
+
+            Ok(response)
+        }
+    }
+
+    pub(super) fn decode_request(body: &str) -> Result<Value, LiqPayError> {
+        let data = body
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(DATA)?.strip_prefix('='))
+            .map(url_decode)
+            .unwrap_or_default();
+        let decoded_data = general_purpose::STANDARD.decode(data)?;
+
+        Ok(serde_json::from_slice(&decoded_data)?)
+    }
+
+    fn url_decode(value: &str) -> String {
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+
+        while index < bytes.len() {
+            let escaped = value
+                .get(index + 1..index + 3)
+                .filter(|_| bytes[index] == b'%')
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    index += 3;
+                }
+                None => {
+                    decoded.push(bytes[index]);
+                    index += 1;
+                }
+            }
+        }
+
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub mod webhook {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+
+    use axum::Form;
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use serde::Deserialize;
+    use serde::de::DeserializeOwned;
+
+    use super::{decode_callback, verify_callback};
+    use crate::common::traits::SignatureAlgorithm;
+
+    type CallbackFuture = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+    /// Represents the form of a callback, which LiqPay sends to the `server_url`.
+    #[derive(Debug, Deserialize)]
+    pub struct CallbackForm {
+        /// Represents the base64-encoded callback payload.
+        pub data: String,
+        /// Represents the signature of the callback payload.
+        pub signature: String,
+    }
+
+    /// Constructs an axum handler for LiqPay callbacks. The handler verifies the signature of a callback,
+    /// decodes its payload into `Resp` (e.g. `StatusResponse`) and passes it to `handler`.
+    /// Callbacks with an invalid signature or an undecodable payload are rejected with `400 Bad Request`.
+    pub fn callback_layer<Resp, Alg, F, Fut, Out>(
+        private_key: impl Into<String>,
+        handler: F,
+    ) -> impl Fn(Form<CallbackForm>) -> CallbackFuture + Clone + Send + Sync + 'static
+    where
+        Resp: DeserializeOwned + Send + 'static,
+        Alg: SignatureAlgorithm + 'static,
+        F: Fn(Resp) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Out> + Send + 'static,
+        Out: IntoResponse + 'static,
+    {
+        let private_key: Arc<str> = Arc::from(private_key.into());
+        let handler = Arc::new(handler);
+
+        move |Form(form): Form<CallbackForm>| -> CallbackFuture {
+            let private_key = Arc::clone(&private_key);
+            let handler = Arc::clone(&handler);
+
+            Box::pin(async move {
+                if !verify_callback::<Alg>(&private_key, &form.data, &form.signature) {
+                    return StatusCode::BAD_REQUEST.into_response();
+                }
+
+                match decode_callback::<Resp>(&form.data) {
+                    Ok(callback) => handler(callback).await.into_response(),
+                    Err(_) => StatusCode::BAD_REQUEST.into_response(),
+                }
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use base64::Engine;
+        use base64::engine::general_purpose;
+        use sha3::Sha3_256;
+
+        use super::*;
+        use crate::client::expected_callback_signature;
+        use crate::informational::status::StatusResponse;
+
+        const PRIVATE_KEY: &str = "private_key";
+
+        async fn call(data: String, signature: String) -> (StatusCode, bool) {
+            let handled = Arc::new(AtomicBool::new(false));
+            let handler = {
+                let handled = Arc::clone(&handled);
+
+                callback_layer::<StatusResponse, Sha3_256, _, _, _>(
+                    PRIVATE_KEY,
+                    move |_: StatusResponse| {
+                        handled.store(true, Ordering::SeqCst);
+                        async { StatusCode::OK }
+                    },
+                )
+            };
+
+            let response = handler(Form(CallbackForm { data, signature })).await;
+
+            (response.status(), handled.load(Ordering::SeqCst))
+        }
+
+        fn signed(data: &str) -> (String, String) {
+            let data = general_purpose::STANDARD.encode(data);
+            let signature = expected_callback_signature::<Sha3_256>(PRIVATE_KEY, &data);
+
+            (data, signature)
+        }
+
+        #[tokio::test]
+        async fn callback_with_valid_signature_is_handled() {
+            let (data, signature) = signed(r#"{"result":"ok","status":"success"}"#);
+
+            assert_eq!(call(data, signature).await, (StatusCode::OK, true));
+        }
+
+        #[tokio::test]
+        async fn callback_with_invalid_signature_is_rejected() {
+            let (data, _) = signed(r#"{"result":"ok","status":"success"}"#);
+
+            assert_eq!(
+                call(data, "signature".to_owned()).await,
+                (StatusCode::BAD_REQUEST, false)
+            );
+        }
+
+        #[tokio::test]
+        async fn callback_with_undecodable_payload_is_rejected() {
+            let (data, signature) = signed("garbage");
+
+            assert_eq!(
+                call(data, signature).await,
+                (StatusCode::BAD_REQUEST, false)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use reqwest::header::{HeaderName, HeaderValue};
+    use serde_json::json;
+    use sha1::Sha1;
+    use sha3::Sha3_256;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::test_util::{MockTransport, decode_request};
+    use super::*;
+    use crate::common::enums::{Currency, Result as LiqPayResult, Status};
+    use crate::informational::status::StatusRequest;
+    #[cfg(feature = "tracing")]
+    use crate::internet_acquiring::card::CardPaymentRequest;
+    use crate::p2p::{P2PRequest, SenderCard};
+    use crate::partner::create_company::MccCodesRequest;
+
+    const PUBLIC_KEY: &str = "public_key";
+    const PRIVATE_KEY: &str = "private_key";
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    fn signed_data<Req, Resp, Alg>(request: Req) -> Value
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        signed_data_with_config(request, &ClientConfig::new())
+    }
+
+    fn signed_data_with_config<Req, Resp, Alg>(request: Req, config: &ClientConfig) -> Value
+    where
+        Req: LiqPayRequest<Resp, Alg> + Serialize,
+        Resp: LiqPayResponse + DeserializeOwned,
+        Alg: SignatureAlgorithm,
+    {
+        let params = CheckoutParams::new(request, PRIVATE_KEY, config).unwrap();
+
+        decode_callback(&params.data).unwrap()
+    }
+
+    fn status_request() -> RawRequest<Sha3_256> {
+        RawRequest::new(PUBLIC_KEY, "status").param("order_id", "order")
+    }
+
+    fn mock_client(config: ClientConfig, transport: MockTransport) -> LiqPayClient<MockTransport> {
+        LiqPayClient::with_transport(PRIVATE_KEY, config, transport)
+    }
+
+    fn response(
+        status: StatusCode,
+        header: Option<(HeaderName, &'static str)>,
+    ) -> TransportResponse {
+        let mut headers = HeaderMap::new();
+
+        if let Some((name, value)) = header {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+
+        TransportResponse {
+            status,
+            headers,
+            body: String::new(),
+        }
+    }
+
+    fn p2p_request() -> P2PRequest {
+        P2PRequest::with_value(
+            PUBLIC_KEY,
+            (100.0, Currency::UAH),
+            SenderCard::new(
+                "4242424242424242".to_owned(),
+                "123".to_owned(),
+                "12".to_owned(),
+                "30".to_owned(),
+            ),
+            "5375414100000000".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    /// Replies to each request with the response built by `reply` from its decoded `data`,
+    /// after yielding `1 + delay` times, where `delay` is the optional parameter of the request.
+    /// Tracks the greatest number of requests in flight.
+    struct FnTransport<F> {
+        reply: F,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    impl<F> FnTransport<F> {
+        fn new(reply: F) -> Self {
+            Self {
+                reply,
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl<F> Transport for FnTransport<F>
+    where
+        F: Fn(&Value) -> TransportResponse + Sync,
+    {
+        async fn execute(&self, _url: &str, body: &str) -> Result<TransportResponse, LiqPayError> {
+            let request = decode_request(body)?;
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+            for _ in 0..=request["delay"].as_u64().unwrap_or(0) {
+                tokio::task::yield_now().await;
+            }
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok((self.reply)(&request))
+        }
+    }
+
+    fn ok_response(body: String) -> TransportResponse {
+        TransportResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body,
+        }
+    }
+
+    #[test]
+    fn version_is_determined_by_signature_algorithm() {
+        let v3_request = signed_data(MccCodesRequest::new(PUBLIC_KEY));
+        let v7_request = signed_data(StatusRequest::new(PUBLIC_KEY, "order".to_owned()));
+
+        assert_eq!(v3_request["version"], "3");
+        assert_eq!(v7_request["version"], "7");
+    }
+
+    #[test]
+    fn action_and_order_id_are_read_from_typed_fields() {
+        let request = StatusRequest::new(PUBLIC_KEY, "order".to_owned());
+
+        assert_eq!(LiqPayRequest::action(&request), Some(Action::Status));
+        assert_eq!(LiqPayRequest::order_id(&request), Some("order"));
+    }
+
+    #[test]
+    fn hand_built_raw_request_is_signed_with_its_algorithm() {
+        let request = RawRequest::<Sha1>::new(PUBLIC_KEY, "agent_custom_action")
+            .param("order_id", "order")
+            .param("amount", 100.0);
+
+        let params = CheckoutParams::new(request, PRIVATE_KEY, &ClientConfig::new()).unwrap();
+
+        assert_eq!(
+            decode_callback::<Value>(&params.data).unwrap(),
+            json!({
+                "version": "3",
+                "public_key": PUBLIC_KEY,
+                "action": "agent_custom_action",
+                "order_id": "order",
+                "amount": 100.0,
+            })
+        );
+        assert_eq!(params.signature, sign::<Sha1>(PRIVATE_KEY, &params.data));
+    }
+
+    #[test]
+    fn raw_request_action_is_parsed_from_its_name() {
+        let known = RawRequest::<Sha3_256>::new(PUBLIC_KEY, "status").param("order_id", "order");
+        let unknown = RawRequest::<Sha3_256>::new(PUBLIC_KEY, "unknown_action");
+
+        assert_eq!(LiqPayRequest::action(&known), Some(Action::Status));
+        assert_eq!(LiqPayRequest::order_id(&known), Some("order"));
+        assert_eq!(LiqPayRequest::action(&unknown), None);
+    }
+
+    #[test]
+    fn raw_request_is_built_and_signed_from_json_object() {
+        let request = RawRequest::<Sha3_256>::from_value(
+            PUBLIC_KEY,
+            "status",
+            json!({ "order_id": "order", "action": "pay" }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            signed_data(request),
+            json!({
+                "version": "7",
+                "public_key": PUBLIC_KEY,
+                "action": "status",
+                "order_id": "order",
+            })
+        );
+
+        let request = RawRequest::<Sha3_256>::from_value(
+            PUBLIC_KEY,
+            "status",
+            json!({ "public_key": "other_public_key", "version": 7 }),
+        )
+        .unwrap();
+
+        assert_eq!(signed_data(request)["public_key"], "other_public_key");
+        assert!(RawRequest::<Sha3_256>::from_value(PUBLIC_KEY, "status", json!([])).is_err());
+    }
+
+    #[test]
+    fn raw_request_rejects_version_of_another_algorithm() {
+        let params = json!({ "version": 7, "order_id": "order" });
+
+        assert!(RawRequest::<Sha1>::from_value(PUBLIC_KEY, "status", params.clone()).is_err());
+        assert!(RawRequest::<Sha3_256>::from_value(PUBLIC_KEY, "status", params).is_ok());
+    }
+
+    #[test]
+    fn checkout_params_serialize_to_data_and_signature_only() {
+        let params =
+            CheckoutParams::new(status_request(), PRIVATE_KEY, &ClientConfig::new()).unwrap();
+
+        let value = serde_json::to_value(&params).unwrap();
+
+        assert_eq!(
+            value,
+            json!({ "data": params.data, "signature": sign::<Sha3_256>(PRIVATE_KEY, &params.data) })
+        );
+        assert_eq!(value.as_object().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn config_sandbox_is_signed_into_requests_supporting_it() {
+        let config = ClientConfig::new().sandbox(true);
+
+        let p2p_request = signed_data_with_config(p2p_request(), &config);
+        let status_request =
+            signed_data_with_config(StatusRequest::new(PUBLIC_KEY, "order".to_owned()), &config);
+
+        assert_eq!(p2p_request["sandbox"], "1");
+        assert!(status_request.get("sandbox").is_none());
+    }
+
+    #[test]
+    fn explicit_sandbox_is_kept_without_config_sandbox() {
+        let request = signed_data(p2p_request().sandbox());
+
+        assert_eq!(request["sandbox"], "1");
+    }
+
+    #[test]
+    fn form_body_and_checkout_params_apply_the_same_defaults() {
+        let config = ClientConfig::new().sandbox(true);
+
+        let params = CheckoutParams::new(p2p_request(), PRIVATE_KEY, &config).unwrap();
+        let body = encode_form_body(p2p_request(), PRIVATE_KEY, &config).unwrap();
+
+        assert_eq!(
+            body,
+            format!(
+                "data={}&signature={}",
+                url_encode(&params.data),
+                url_encode(&params.signature)
+            )
+        );
+    }
+
+    #[test]
+    fn reserved_characters_are_percent_encoded() {
+        assert_eq!(url_encode("a+b/c=d"), "a%2Bb%2Fc%3Dd");
+        assert_eq!(url_encode("Az09-._~"), "Az09-._~");
+        assert_eq!(
+            encode_form_data(&[
+                ("data", "eyJh+/==".to_owned()),
+                ("signature", "q8/+x=".to_owned()),
+            ]),
+            "data=eyJh%2B%2F%3D%3D&signature=q8%2F%2Bx%3D"
+        );
+    }
+
+    #[test]
+    fn default_language_fills_unset_language() {
+        let config = ClientConfig::new().default_language(Language::Uk);
+
+        let request = signed_data_with_config(MccCodesRequest::new(PUBLIC_KEY), &config);
+
+        assert_eq!(request["language"], "uk");
+    }
+
+    #[test]
+    fn explicit_language_is_not_overridden_by_default_language() {
+        let config = ClientConfig::new().default_language(Language::Uk);
+
+        let request = signed_data_with_config(
+            MccCodesRequest::new(PUBLIC_KEY).language(Language::En),
+            &config,
+        );
+
+        assert_eq!(request["language"], "en");
+    }
+
+    #[test]
+    fn default_language_is_not_added_to_requests_without_language() {
+        let config = ClientConfig::new().default_language(Language::Uk);
+
+        let request =
+            signed_data_with_config(StatusRequest::new(PUBLIC_KEY, "order".to_owned()), &config);
+
+        assert!(request.get("language").is_none());
+    }
+
+    #[tokio::test]
+    async fn send_returns_parsed_response() {
+        let transport =
+            MockTransport::new().respond("status", r#"{"result":"ok","status":"success"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = client.send(status_request()).await.unwrap();
+        let requests = client.transport.requests();
+
+        assert_eq!(response, json!({ "result": "ok", "status": "success" }));
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["order_id"], "order");
+    }
+
+    #[tokio::test]
+    async fn send_fails_on_undecodable_response() {
+        let transport = MockTransport::new().respond("status", "not a JSON body");
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let error = client.send(status_request()).await.unwrap_err();
+
+        assert!(matches!(error, LiqPayError::Serialization(_)));
+    }
+
+    #[tokio::test]
+    async fn send_retries_after_rate_limit() {
+        let transport = MockTransport::new()
+            .respond_with(
+                "status",
+                response(StatusCode::TOO_MANY_REQUESTS, Some((RETRY_AFTER, "0"))),
+            )
+            .respond("status", r#"{"result":"ok"}"#);
+        let client = mock_client(ClientConfig::new().max_retries(1), transport);
+
+        let response = client.send(status_request()).await.unwrap();
+
+        assert_eq!(response, json!({ "result": "ok" }));
+        assert_eq!(client.transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn send_fails_with_rate_limit_once_retries_are_exhausted() {
+        let transport = MockTransport::new().respond_with(
+            "status",
+            response(StatusCode::TOO_MANY_REQUESTS, Some((RETRY_AFTER, "7"))),
+        );
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let error = client.send(status_request()).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            LiqPayError::RateLimited {
+                retry_after: Some(retry_after)
+            } if retry_after == Duration::from_secs(7)
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_idempotent_actions_are_retried() {
+        let transport = MockTransport::new()
+            .respond_with(
+                "status",
+                response(StatusCode::TOO_MANY_REQUESTS, Some((RETRY_AFTER, "0"))),
+            )
+            .respond("status", r#"{"result":"ok"}"#)
+            .respond_with(
+                "pay",
+                response(StatusCode::TOO_MANY_REQUESTS, Some((RETRY_AFTER, "0"))),
+            );
+        let client = mock_client(ClientConfig::new().max_retries(1), transport);
+        let payment = RawRequest::<Sha3_256>::new(PUBLIC_KEY, "pay").param("order_id", "order");
+
+        let status = client.send(status_request()).await;
+        let payment = client.send(payment).await;
+
+        let actions: Vec<_> = client
+            .transport
+            .requests()
+            .into_iter()
+            .map(|request| request["action"].clone())
+            .collect();
+        assert!(status.is_ok());
+        assert!(matches!(payment, Err(LiqPayError::RateLimited { .. })));
+        assert_eq!(actions, ["status", "status", "pay"]);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_without_retry_after_has_no_delay() {
+        let transport = MockTransport::new()
+            .respond_with("status", response(StatusCode::TOO_MANY_REQUESTS, None));
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let error = client.send(status_request()).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            LiqPayError::RateLimited { retry_after: None }
+        ));
+    }
+
+    #[tokio::test]
+    async fn send_fails_with_unavailable_on_html_page() {
+        let transport = MockTransport::new().respond_with(
+            "status",
+            response(
+                StatusCode::SERVICE_UNAVAILABLE,
+                Some((CONTENT_TYPE, "text/html")),
+            ),
+        );
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let error = client.send(status_request()).await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            LiqPayError::Unavailable { status: 503, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn retried_request_is_sent_with_identical_data_and_signature() {
+        let transport = MockTransport::new()
+            .respond_with(
+                "status",
+                response(StatusCode::TOO_MANY_REQUESTS, Some((RETRY_AFTER, "0"))),
+            )
+            .respond("status", r#"{"result":"ok"}"#);
+        let client = mock_client(ClientConfig::new().max_retries(1), transport);
+
+        client.send(status_request()).await.unwrap();
+
+        let bodies = client.transport.bodies();
+        let (data, signature) = client.dry_run(status_request()).unwrap();
+
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0], bodies[1]);
+        assert_eq!(
+            bodies[0],
+            encode_form_data(&[(DATA, data.clone()), (SIGNATURE, signature)])
+        );
+        assert_eq!(client.last_request_data(), Some(data));
+    }
+
+    /// Accepts a single connection and replies to the request with a gzip-compressed `body`.
+    async fn serve_gzip(listener: TcpListener, body: &str) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..read]);
+
+            let request = String::from_utf8_lossy(&request);
+
+            if let Some((head, sent_body)) = request.split_once("\r\n\r\n") {
+                let content_length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|value| value.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+
+                if read == 0 || sent_body.len() >= content_length {
+                    break;
+                }
+            }
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-encoding: gzip\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+            compressed.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(&compressed).await.unwrap();
+        stream.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gzip_response_is_decompressed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_gzip(
+            listener,
+            r#"{"result":"ok","status":"success"}"#,
+        ));
+        let transport = HttpTransport::new(&ClientConfig::new().decompression(true)).unwrap();
+
+        let response = transport
+            .execute(&url, "data=data&signature=signature")
+            .await
+            .unwrap();
+        let response: Value = parse_response(response).unwrap();
+
+        assert_eq!(response, json!({ "result": "ok", "status": "success" }));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_with_raw_keeps_fields_dropped_by_typed_response() {
+        let transport = MockTransport::new().respond(
+            "agent_info_mcc_codes",
+            r#"{"result":"ok","status":"success","mcc_codes":[],"new_field":"value"}"#,
+        );
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let (response, raw_response) = client
+            .send_with_raw(MccCodesRequest::new(PUBLIC_KEY))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status, Status::Success);
+        assert_eq!(response.mcc_codes, Some(Vec::new()));
+        assert_eq!(raw_response["new_field"], "value");
+    }
+
+    #[test]
+    fn serialized_data_does_not_depend_on_parameter_order() {
+        let client = mock_client(ClientConfig::new(), MockTransport::new());
+        let forward = || {
+            RawRequest::<Sha3_256>::new(PUBLIC_KEY, "status")
+                .param("order_id", "order")
+                .param("language", "uk")
+                .param("amount", 100.0)
+        };
+        let backward = RawRequest::<Sha3_256>::new(PUBLIC_KEY, "status")
+            .param("amount", 100.0)
+            .param("language", "uk")
+            .param("order_id", "order");
+
+        let first = client.dry_run(forward()).unwrap();
+
+        assert_eq!(client.dry_run(forward()).unwrap(), first);
+        assert_eq!(client.dry_run(backward).unwrap(), first);
+    }
+
+    #[tokio::test]
+    async fn dry_run_produces_data_and_signature_sent_by_send() {
+        let transport = MockTransport::new().respond("status", r#"{"result":"ok"}"#);
+        let client = mock_client(ClientConfig::new().sandbox(true), transport);
+
+        let (data, signature) = client.dry_run(status_request()).unwrap();
+        client.send(status_request()).await.unwrap();
+
+        assert_eq!(
+            client.transport.bodies(),
+            [encode_form_data(&[
+                ("data", data.clone()),
+                ("signature", signature)
+            ])]
+        );
+        assert_eq!(client.last_request_data(), Some(data));
+    }
+
+    #[tokio::test]
+    async fn capture_completes_the_hold_with_given_amount() {
+        let transport = MockTransport::new()
+            .respond("hold_completion", r#"{"result":"ok","status":"success"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = capture(&client, PUBLIC_KEY, "order", 50.0).await.unwrap();
+
+        let requests = client.transport.requests();
+        assert_eq!(response.payment.status, Status::Success);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["action"], "hold_completion");
+        assert_eq!(requests[0]["order_id"], "order");
+        assert_eq!(requests[0]["amount"], 50.0);
+    }
+
+    #[tokio::test]
+    async fn reverse_refunds_the_hold_with_given_amount() {
+        let transport =
+            MockTransport::new().respond("refund", r#"{"result":"ok","status":"reversed"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = reverse(&client, PUBLIC_KEY, "order", 50.0).await.unwrap();
+
+        let requests = client.transport.requests();
+        assert_eq!(response.status, Status::Reversed);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["action"], "refund");
+        assert_eq!(requests[0]["order_id"], "order");
+        assert_eq!(requests[0]["amount"], 50.0);
+    }
+
+    #[tokio::test]
+    async fn send_all_caps_concurrency_and_preserves_order() {
+        let transport = FnTransport::new(|request: &Value| {
+            ok_response(json!({ "result": "ok", "order_id": request["order_id"] }).to_string())
+        });
+        let client = LiqPayClient::with_transport(
+            PRIVATE_KEY,
+            ClientConfig::new().max_concurrency(2),
+            transport,
+        );
+        let requests = (0..5).map(|index| {
+            RawRequest::<Sha3_256>::new(PUBLIC_KEY, "status")
+                .param("order_id", format!("order_{index}"))
+                .param("delay", 5 - index)
+        });
+
+        let responses = client.send_all(requests).await;
+
+        let order_ids: Vec<_> = responses
+            .into_iter()
+            .map(|response| response.unwrap()["order_id"].clone())
+            .collect();
+        assert_eq!(
+            order_ids,
+            ["order_0", "order_1", "order_2", "order_3", "order_4"]
+        );
+        assert_eq!(client.transport.max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn token_statuses_are_changed_independently() {
+        let transport = FnTransport::new(|request: &Value| match request["card_token"].as_str() {
+            Some("suspended") => ok_response(
+                json!({ "result": "ok", "status": "success", "card_token": "suspended" })
+                    .to_string(),
+            ),
+            Some("unknown") => {
+                ok_response(json!({ "result": "error", "err_code": "token_not_found" }).to_string())
+            }
+            _ => TransportResponse {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                headers: HeaderMap::new(),
+                body: "Service Unavailable".to_owned(),
+            },
+        });
+        let client = LiqPayClient::with_transport(PRIVATE_KEY, ClientConfig::new(), transport);
+        let changes = vec![
+            ("suspended".to_owned(), CardTokenAction::Suspend),
+            ("unknown".to_owned(), CardTokenAction::Unsuspend),
+            ("unavailable".to_owned(), CardTokenAction::Delete),
+        ];
+
+        let responses = change_token_statuses(&client, PUBLIC_KEY, changes).await;
+
+        assert_eq!(responses.len(), 3);
+        assert!(matches!(
+            &responses[0],
+            Ok(response) if response.result == LiqPayResult::Ok
+                && response.card_token.as_deref() == Some("suspended")
+        ));
+        assert!(matches!(
+            &responses[1],
+            Ok(response) if response.result == LiqPayResult::Error
+                && response.error_code.as_deref() == Some("token_not_found")
+        ));
+        assert!(matches!(responses[2], Err(LiqPayError::Unavailable { .. })));
+    }
+
+    #[tokio::test]
+    async fn reference_cache_requests_list_once_within_ttl() {
+        let transport = MockTransport::new().respond(
+            "agent_info_mcc_codes",
+            r#"{"result":"ok","status":"success","mcc_codes":[]}"#,
+        );
+        let client = mock_client(ClientConfig::new(), transport);
+        let cache = ReferenceCache::new(client, PUBLIC_KEY, Duration::from_secs(60));
+
+        cache.get_mcc_codes(Language::En).await.unwrap();
+        cache.get_mcc_codes(Language::En).await.unwrap();
+        assert_eq!(cache.client.transport.requests().len(), 1);
+
+        cache.get_mcc_codes(Language::Uk).await.unwrap();
+        assert_eq!(cache.client.transport.requests().len(), 2);
+
+        cache.clear();
+        cache.get_mcc_codes(Language::En).await.unwrap();
+        assert_eq!(cache.client.transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn reference_cache_requests_list_again_once_ttl_elapses() {
+        let transport = MockTransport::new().respond(
+            "agent_info_mcc_codes",
+            r#"{"result":"ok","status":"success","mcc_codes":[]}"#,
+        );
+        let client = mock_client(ClientConfig::new(), transport);
+        let cache = ReferenceCache::new(client, PUBLIC_KEY, Duration::ZERO);
+
+        cache.get_mcc_codes(Language::En).await.unwrap();
+        cache.get_mcc_codes(Language::En).await.unwrap();
+
+        assert_eq!(cache.client.transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reference_cache_does_not_cache_errors() {
+        let transport = MockTransport::new()
+            .respond(
+                "agent_info_mcc_codes",
+                r#"{"result":"error","status":"error","err_code":"err_access"}"#,
+            )
+            .respond(
+                "agent_info_mcc_codes",
+                r#"{"result":"ok","status":"success","mcc_codes":[]}"#,
+            );
+        let client = mock_client(ClientConfig::new(), transport);
+        let cache = ReferenceCache::new(client, PUBLIC_KEY, Duration::from_secs(60));
+
+        let failed = cache.get_mcc_codes(Language::En).await.unwrap();
+        let succeeded = cache.get_mcc_codes(Language::En).await.unwrap();
+        cache.get_mcc_codes(Language::En).await.unwrap();
+
+        assert_eq!(failed.result, LiqPayResult::Error);
+        assert_eq!(succeeded.result, LiqPayResult::Ok);
+        assert_eq!(cache.client.transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn archive_entries_are_streamed_day_by_day() {
+        let transport = MockTransport::new()
+            .respond(
+                "reports",
+                r#"{"result":"ok","data":[{"status":"success","order_id":"1"}]}"#,
+            )
+            .respond(
+                "reports",
+                r#"{"result":"ok","data":[{"status":"success","order_id":"2"},{"status":"failure","order_id":"3"}]}"#,
+            );
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let entries: Vec<_> = archive_entries(&client, PUBLIC_KEY, 0, 2 * DAY - 1)
+            .collect()
+            .await;
+        let order_ids: Vec<_> = entries
+            .into_iter()
+            .map(|entry| entry.unwrap().order_id.unwrap())
+            .collect();
+
+        assert_eq!(order_ids, ["1", "2", "3"]);
+        assert_eq!(client.transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn archive_entries_stream_ends_after_first_error() {
+        let transport = MockTransport::new()
+            .respond(
+                "reports",
+                r#"{"result":"ok","data":[{"status":"success","order_id":"1"}]}"#,
+            )
+            .respond_with(
+                "reports",
+                response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Some((CONTENT_TYPE, "text/html")),
+                ),
+            );
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let entries: Vec<_> = archive_entries(&client, PUBLIC_KEY, 0, 3 * DAY - 1)
+            .collect()
+            .await;
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].is_ok());
+        assert!(matches!(
+            entries[1],
+            Err(LiqPayError::Unavailable { status: 503, .. })
+        ));
+        assert_eq!(client.transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn poll_status_returns_once_status_is_final() {
+        let transport = MockTransport::new()
+            .respond("status", r#"{"result":"ok","status":"processing"}"#)
+            .respond("status", r#"{"result":"ok","status":"processing"}"#)
+            .respond("status", r#"{"result":"ok","status":"success"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = poll_status(
+            &client,
+            PUBLIC_KEY,
+            "order",
+            Duration::ZERO,
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status, Status::Success);
+        assert_eq!(client.transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn poll_status_returns_active_subscription() {
+        let transport =
+            MockTransport::new().respond("status", r#"{"result":"ok","status":"subscribed"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = poll_status(
+            &client,
+            PUBLIC_KEY,
+            "order",
+            Duration::ZERO,
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status, Status::Subscribed);
+        assert_eq!(client.transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn poll_status_returns_last_response_on_timeout() {
+        let transport =
+            MockTransport::new().respond("status", r#"{"result":"ok","status":"processing"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let response = poll_status(
+            &client,
+            PUBLIC_KEY,
+            "order",
+            Duration::from_millis(1),
+            Duration::ZERO,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status, Status::Processing);
+        assert_eq!(client.transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn card_details_are_redacted_for_log() {
+        let data = general_purpose::STANDARD.encode(
+            json!({
+                "card": "4242424242424242",
+                "receiver_card": "5555555555554444",
+                "card_cvv": "123",
+                "card_token": "token",
+                "order_id": "order",
+                "amount": 100.0,
+            })
+            .to_string(),
+        );
+
+        let redacted: Value = serde_json::from_str(&redact_data_for_log(&data)).unwrap();
+
+        assert_eq!(
+            redacted,
+            json!({
+                "card": "****4242",
+                "receiver_card": "****4444",
+                "card_cvv": REDACTED,
+                "card_token": REDACTED,
+                "order_id": "order",
+                "amount": 100.0,
+            })
+        );
+    }
+
+    #[test]
+    fn secret_is_not_printed() {
+        assert_eq!(
+            format!("{:?}", Secret::new(PRIVATE_KEY)),
+            "Secret([REDACTED])"
+        );
+
+        let client = mock_client(ClientConfig::new(), MockTransport::new());
+
+        assert!(format!("{client:?}").contains("private_key: Secret([REDACTED])"));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn request_span_records_action_and_order_id_only() {
+        let transport =
+            MockTransport::new().respond("pay", r#"{"result":"ok","status":"success"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+        let request = CardPaymentRequest::with_value(
+            PUBLIC_KEY,
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+        .cvv("987".to_owned());
+
+        client.send(request).await.unwrap();
+
+        assert!(logs_contain("liqpay_request"));
+        assert!(logs_contain(r#"action="pay""#));
+        assert!(logs_contain(r#"order_id="order""#));
+        assert!(logs_contain("status=200"));
+        assert!(!logs_contain("4242424242424242"));
+        assert!(!logs_contain("987"));
+        assert!(!logs_contain(PRIVATE_KEY));
+    }
+
+    #[test]
+    fn fabricated_callback_passes_verification() {
+        let data = general_purpose::STANDARD
+            .encode(r#"{"order_id":"order","status":"success","action":"pay"}"#);
+
+        let signature = expected_callback_signature::<Sha3_256>(PRIVATE_KEY, &data);
+
+        assert!(verify_callback::<Sha3_256>(PRIVATE_KEY, &data, &signature));
+        assert!(!verify_callback::<Sha3_256>("other_key", &data, &signature));
+        assert!(!verify_callback::<Sha1>(PRIVATE_KEY, &data, &signature));
+    }
+
+    #[tokio::test]
+    async fn v3_and_v7_requests_are_sent_to_the_same_endpoint() {
+        let transport = MockTransport::new()
+            .respond("agent_info_mcc_codes", r#"{"result":"ok","data":[]}"#)
+            .respond("status", r#"{"result":"ok","status":"success"}"#);
+        let client = mock_client(ClientConfig::new(), transport);
+
+        let _ = client.send(MccCodesRequest::new(PUBLIC_KEY)).await;
+        let _ = client
+            .send(StatusRequest::new(PUBLIC_KEY, "order".to_owned()))
+            .await;
+
+        let versions: Vec<_> = client
+            .transport
+            .requests()
+            .iter()
+            .map(|request| request["version"].clone())
+            .collect();
+        assert_eq!(versions, [json!("3"), json!("7")]);
+        assert_eq!(client.transport.urls(), [CLIENT_URL; 2]);
     }
 }
@@ -1,27 +1,80 @@
 pub mod traits {
     use serde::de::DeserializeOwned;
-    use sha1::Digest;
+    use sha1::{Digest, Sha1};
+    use sha3::Sha3_256;
+
+    use crate::client::ClientConfig;
+    use crate::common::enums::{Action, Version};
 
     /// Represents a request to the LiqPay system.
     pub trait LiqPayRequest<Resp, Alg>
     where
         Resp: LiqPayResponse + DeserializeOwned,
-        Alg: Digest,
+        Alg: SignatureAlgorithm,
     {
+        /// Returns the action of the request, or `None` if it is not one of the known actions.
+        fn action(&self) -> Option<Action>;
+
+        /// Returns the order identifier of the request, or `None` if the request has no order identifier.
+        fn order_id(&self) -> Option<&str> {
+            None
+        }
+
+        /// Returns the API version of the request, which is determined by its signature algorithm.
+        fn version(&self) -> Version {
+            Alg::VERSION
+        }
+
+        /// Fills the fields of the request, which are not set explicitly, with the defaults of `config`.
+        /// Only the fields the request declares are filled, so requests without such fields are left as is.
+        fn apply_defaults(&mut self, _config: &ClientConfig) {}
+    }
+
+    /// Represents an algorithm, which requests are signed with.
+    /// Each API version has its own algorithm, so the version is sent based on the algorithm.
+    pub trait SignatureAlgorithm: Digest + sealed::Sealed {
+        /// Represents the API version, which requests signed with the algorithm are sent to.
+        const VERSION: Version;
+    }
+
+    impl SignatureAlgorithm for Sha1 {
+        const VERSION: Version = Version::Three;
+    }
+
+    impl SignatureAlgorithm for Sha3_256 {
+        const VERSION: Version = Version::Seven;
+    }
+
+    mod sealed {
+        pub trait Sealed {}
+
+        impl Sealed for sha1::Sha1 {}
+        impl Sealed for sha3::Sha3_256 {}
     }
 
     /// Represents a response from a LiqPay system.
     pub trait LiqPayResponse {}
+
+    /// Represents a response, which may require a customer to pass a 3D Secure challenge.
+    pub trait ThreeDsChallenge {
+        /// Returns the URL, the customer's browser must visit to pass the challenge,
+        /// or `None` if no challenge is required.
+        fn challenge_url(&self) -> Option<&str>;
+    }
 }
 
 pub mod enums {
+    use std::fmt::{self, Display, Formatter};
     use std::io::{Error, ErrorKind};
     use std::result::Result as StdResult;
+    use std::str::FromStr;
 
     use serde::{Deserialize, Serialize, de};
 
+    use crate::common::amount::Amount;
+
     /// Represents the API version.
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Clone, PartialEq, Serialize)]
     pub enum Version {
         #[serde(rename = "3")]
         Three,
@@ -29,6 +82,16 @@ pub mod enums {
         Seven,
     }
 
+    impl Version {
+        /// Returns the numeric representation of the version, e.g. `7` for `Version::Seven`.
+        pub fn as_u8(&self) -> u8 {
+            match self {
+                Version::Three => 3,
+                Version::Seven => 7,
+            }
+        }
+    }
+
     impl TryFrom<u8> for Version {
         type Error = Error;
 
@@ -44,19 +107,44 @@ pub mod enums {
         }
     }
 
+    impl TryFrom<&str> for Version {
+        type Error = Error;
+
+        fn try_from(value: &str) -> StdResult<Self, Self::Error> {
+            let version: u8 = value.trim().parse().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Version {} is not supported.", value),
+                )
+            })?;
+
+            Version::try_from(version)
+        }
+    }
+
     impl<'de> Deserialize<'de> for Version {
         fn deserialize<D>(deserializer: D) -> StdResult<Self, D::Error>
         where
             D: serde::Deserializer<'de>,
         {
-            let deserialized_version = u8::deserialize(deserializer)?;
+            #[derive(Deserialize)]
+            #[serde(untagged)]
+            enum VersionRepr {
+                Number(u8),
+                String(String),
+            }
+
+            let deserialized_version = match VersionRepr::deserialize(deserializer)? {
+                VersionRepr::Number(version) => Version::try_from(version),
+                VersionRepr::String(version) => Version::try_from(version.as_str()),
+            };
 
-            Version::try_from(deserialized_version).map_err(de::Error::custom)
+            deserialized_version.map_err(de::Error::custom)
         }
     }
 
     /// Represents an action.
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum Action {
         #[serde(rename = "pay")]
         Pay,
@@ -148,8 +236,84 @@ pub mod enums {
         Mpi,
     }
 
+    impl Action {
+        /// Returns the string representation of the action as it is sent to and received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Action::Pay => "pay",
+                Action::SendInvoice => "invoice_send",
+                Action::CancelInvoice => "invoice_cancel",
+                Action::PayQrCode => "payqr",
+                Action::CreateQrCode => "staticQrCreate",
+                Action::PayToken => "paytoken",
+                Action::PayCash => "paycash",
+                Action::PayTrack => "paytrack",
+                Action::Refund => "refund",
+                Action::Hold => "hold",
+                Action::HoldCompletion => "hold_completion",
+                Action::Subscribe => "subscribe",
+                Action::UpdateSubscription => "subscribe_update",
+                Action::PayDonate => "paydonate",
+                Action::Auth => "auth",
+                Action::Status => "status",
+                Action::Unsubscribe => "unsubscribe",
+                Action::Ticket => "ticket",
+                Action::PaySplit => "paysplit",
+                Action::Regular => "regular",
+                Action::PreparePayment => "payment_prepare",
+                Action::P2PCredit => "p2pcredit",
+                Action::P2PDebit => "p2pdebit",
+                Action::P2P => "p2p",
+                Action::CardVerification => "cardverification",
+                Action::Reports => "reports",
+                Action::CreateToken => "token_create",
+                Action::CreateUniqueToken => "token_create_unique",
+                Action::UpdateToken => "token_update",
+                Action::ReportsCompensation => "reports_compensation",
+                Action::ReportsCompensationFile => "reports_compensation_file",
+                Action::ReportsCompensationFileStatus => "reports_compensation_file_status",
+                Action::Registry => "register",
+                Action::Data => "data",
+                Action::CreateShop => "agent_shop_create",
+                Action::RegisterShop => "agent_shop_register",
+                Action::EditShop => "agent_shop_edit",
+                Action::MccCodes => "agent_info_mcc_codes",
+                Action::MerchantInfo => "agent_info_merchant",
+                Action::UserInfo => "agent_info_user",
+                Action::GetInvoiceUnits => "invoice_units_get_list",
+                Action::GetInvoiceUnitsByLanguage => "invoice_units_get_list_by_lang",
+                Action::Confirm => "confirm",
+                Action::Mpi => "mpi",
+            }
+        }
+
+        /// Indicates whether the action only reads data, so that repeating it has no side effects,
+        /// e.g. `status`, `reports` or `agent_info_mcc_codes`.
+        pub fn is_idempotent(&self) -> bool {
+            matches!(
+                self,
+                Action::Status
+                    | Action::Reports
+                    | Action::ReportsCompensation
+                    | Action::ReportsCompensationFileStatus
+                    | Action::Registry
+                    | Action::MccCodes
+                    | Action::MerchantInfo
+                    | Action::UserInfo
+                    | Action::GetInvoiceUnits
+                    | Action::GetInvoiceUnitsByLanguage
+            )
+        }
+    }
+
+    impl Display for Action {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
     /// Represents a bonus type.
-    #[derive(Deserialize, Debug)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum Bonus {
         #[serde(rename = "bonusplus")]
         BonusPlus,
@@ -162,7 +326,7 @@ pub mod enums {
     }
 
     /// Represents a currency.
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
     pub enum Currency {
         #[serde(rename = "UAH")]
         UAH,
@@ -172,8 +336,424 @@ pub mod enums {
         USD,
     }
 
+    impl Currency {
+        /// Returns the string representation of the currency as it is sent to and received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Currency::UAH => "UAH",
+                Currency::EUR => "EUR",
+                Currency::USD => "USD",
+            }
+        }
+    }
+
+    impl Currency {
+        /// Returns the number of decimal places of the currency's minor unit.
+        pub fn decimal_places(&self) -> u32 {
+            match self {
+                Currency::UAH | Currency::EUR | Currency::USD => 2,
+            }
+        }
+
+        /// Returns the symbol of the currency, e.g. `₴` for UAH.
+        pub fn symbol(&self) -> &'static str {
+            match self {
+                Currency::UAH => "₴",
+                Currency::EUR => "€",
+                Currency::USD => "$",
+            }
+        }
+
+        /// Returns the smallest meaningful amount of a payment in the currency, i.e. a hryvnia for UAH
+        /// and a cent for EUR and USD. The value is advisory and is not taken from the LiqPay
+        /// documentation, so the checked builders do not enforce it and only reject amounts that are
+        /// not positive once rounded to the decimal places of the currency.
+        pub fn min_amount(&self) -> Amount {
+            match self {
+                Currency::UAH => Amount::new(1.0),
+                Currency::EUR | Currency::USD => Amount::new(0.01),
+            }
+        }
+    }
+
+    impl Display for Currency {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    impl FromStr for Currency {
+        type Err = Error;
+
+        fn from_str(value: &str) -> StdResult<Self, Self::Err> {
+            match value {
+                "UAH" => Ok(Currency::UAH),
+                "EUR" => Ok(Currency::EUR),
+                "USD" => Ok(Currency::USD),
+                c => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Currency {} is not supported.", c),
+                )),
+            }
+        }
+    }
+
+    impl TryFrom<&str> for Currency {
+        type Error = Error;
+
+        fn try_from(value: &str) -> StdResult<Self, Self::Error> {
+            value.parse()
+        }
+    }
+
+    /// Represents an error code, which LiqPay returns in `err_code`.
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    pub enum ErrorCode {
+        #[serde(rename = "err_auth")]
+        Auth,
+        #[serde(rename = "err_cache")]
+        Cache,
+        #[serde(rename = "user_not_found")]
+        UserNotFound,
+        #[serde(rename = "err_sms_send")]
+        SmsSend,
+        #[serde(rename = "err_sms_otp")]
+        SmsOtp,
+        #[serde(rename = "shop_blocked")]
+        ShopBlocked,
+        #[serde(rename = "shop_not_active")]
+        ShopNotActive,
+        #[serde(rename = "invalid_signature")]
+        InvalidSignature,
+        #[serde(rename = "order_id_empty")]
+        OrderIdEmpty,
+        #[serde(rename = "order_id_duplicate")]
+        OrderIdDuplicate,
+        #[serde(rename = "err_shop_not_agent")]
+        ShopNotAgent,
+        #[serde(rename = "err_card_def_notfound")]
+        CardDefaultNotFound,
+        #[serde(rename = "err_no_card_token")]
+        NoCardToken,
+        #[serde(rename = "err_card_liqpay_def")]
+        CardLiqPayDefault,
+        #[serde(rename = "err_card_type")]
+        CardType,
+        #[serde(rename = "err_card_country")]
+        CardCountry,
+        #[serde(rename = "err_limit_amount")]
+        LimitAmount,
+        #[serde(rename = "err_payment_amount_limit")]
+        PaymentAmountLimit,
+        #[serde(rename = "amount_limit")]
+        AmountLimit,
+        #[serde(rename = "payment_err_sender_card")]
+        SenderCard,
+        #[serde(rename = "payment_processing")]
+        PaymentProcessing,
+        #[serde(rename = "err_payment_discount")]
+        PaymentDiscount,
+        #[serde(rename = "err_wallet")]
+        Wallet,
+        #[serde(rename = "err_get_verify_code")]
+        GetVerifyCode,
+        #[serde(rename = "err_verify_code")]
+        VerifyCode,
+        #[serde(rename = "wait_info")]
+        WaitInfo,
+        #[serde(rename = "err_path")]
+        Path,
+        #[serde(rename = "err_payment_cash_acq")]
+        PaymentCashAcquiring,
+        #[serde(rename = "err_split_amount")]
+        SplitAmount,
+        #[serde(rename = "err_card_receiver_def")]
+        CardReceiverDefault,
+        #[serde(rename = "payment_err_status")]
+        PaymentStatus,
+        #[serde(rename = "public_key_not_found")]
+        PublicKeyNotFound,
+        #[serde(rename = "payment_not_found")]
+        PaymentNotFound,
+        #[serde(rename = "payment_not_subscribed")]
+        PaymentNotSubscribed,
+        #[serde(rename = "wrong_amount_currency")]
+        WrongAmountCurrency,
+        #[serde(rename = "err_amount_hold")]
+        AmountHold,
+        #[serde(rename = "err_access")]
+        Access,
+        #[serde(rename = "err_blocked")]
+        Blocked,
+        #[serde(rename = "err_empty")]
+        Empty,
+        #[serde(rename = "err_empty_phone")]
+        EmptyPhone,
+        #[serde(rename = "err_missing")]
+        Missing,
+        #[serde(rename = "err_wrong")]
+        Wrong,
+        #[serde(rename = "err_wrong_currency")]
+        WrongCurrency,
+        #[serde(rename = "err_phone")]
+        Phone,
+        #[serde(rename = "err_card")]
+        Card,
+        #[serde(rename = "err_card_bin")]
+        CardBin,
+        #[serde(rename = "err_terminal_notfound")]
+        TerminalNotFound,
+        #[serde(rename = "err_commission_notfound")]
+        CommissionNotFound,
+        #[serde(rename = "err_payment_create")]
+        PaymentCreate,
+        #[serde(rename = "err_mpi")]
+        Mpi,
+        #[serde(rename = "err_currency_is_not_allowed")]
+        CurrencyNotAllowed,
+        #[serde(rename = "err_look")]
+        Look,
+        #[serde(rename = "err_mods_empty")]
+        ModsEmpty,
+        #[serde(rename = "payment_err_type")]
+        PaymentType,
+        #[serde(rename = "err_payment_currency")]
+        PaymentCurrency,
+        #[serde(rename = "err_payment_exchangerates")]
+        PaymentExchangeRates,
+        #[serde(rename = "err_signature")]
+        Signature,
+        #[serde(rename = "err_api_action")]
+        ApiAction,
+        #[serde(rename = "err_api_callback")]
+        ApiCallback,
+        #[serde(rename = "err_api_ip")]
+        ApiIp,
+    }
+
+    impl ErrorCode {
+        /// Returns the string representation of the error code as it is received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                ErrorCode::Auth => "err_auth",
+                ErrorCode::Cache => "err_cache",
+                ErrorCode::UserNotFound => "user_not_found",
+                ErrorCode::SmsSend => "err_sms_send",
+                ErrorCode::SmsOtp => "err_sms_otp",
+                ErrorCode::ShopBlocked => "shop_blocked",
+                ErrorCode::ShopNotActive => "shop_not_active",
+                ErrorCode::InvalidSignature => "invalid_signature",
+                ErrorCode::OrderIdEmpty => "order_id_empty",
+                ErrorCode::OrderIdDuplicate => "order_id_duplicate",
+                ErrorCode::ShopNotAgent => "err_shop_not_agent",
+                ErrorCode::CardDefaultNotFound => "err_card_def_notfound",
+                ErrorCode::NoCardToken => "err_no_card_token",
+                ErrorCode::CardLiqPayDefault => "err_card_liqpay_def",
+                ErrorCode::CardType => "err_card_type",
+                ErrorCode::CardCountry => "err_card_country",
+                ErrorCode::LimitAmount => "err_limit_amount",
+                ErrorCode::PaymentAmountLimit => "err_payment_amount_limit",
+                ErrorCode::AmountLimit => "amount_limit",
+                ErrorCode::SenderCard => "payment_err_sender_card",
+                ErrorCode::PaymentProcessing => "payment_processing",
+                ErrorCode::PaymentDiscount => "err_payment_discount",
+                ErrorCode::Wallet => "err_wallet",
+                ErrorCode::GetVerifyCode => "err_get_verify_code",
+                ErrorCode::VerifyCode => "err_verify_code",
+                ErrorCode::WaitInfo => "wait_info",
+                ErrorCode::Path => "err_path",
+                ErrorCode::PaymentCashAcquiring => "err_payment_cash_acq",
+                ErrorCode::SplitAmount => "err_split_amount",
+                ErrorCode::CardReceiverDefault => "err_card_receiver_def",
+                ErrorCode::PaymentStatus => "payment_err_status",
+                ErrorCode::PublicKeyNotFound => "public_key_not_found",
+                ErrorCode::PaymentNotFound => "payment_not_found",
+                ErrorCode::PaymentNotSubscribed => "payment_not_subscribed",
+                ErrorCode::WrongAmountCurrency => "wrong_amount_currency",
+                ErrorCode::AmountHold => "err_amount_hold",
+                ErrorCode::Access => "err_access",
+                ErrorCode::Blocked => "err_blocked",
+                ErrorCode::Empty => "err_empty",
+                ErrorCode::EmptyPhone => "err_empty_phone",
+                ErrorCode::Missing => "err_missing",
+                ErrorCode::Wrong => "err_wrong",
+                ErrorCode::WrongCurrency => "err_wrong_currency",
+                ErrorCode::Phone => "err_phone",
+                ErrorCode::Card => "err_card",
+                ErrorCode::CardBin => "err_card_bin",
+                ErrorCode::TerminalNotFound => "err_terminal_notfound",
+                ErrorCode::CommissionNotFound => "err_commission_notfound",
+                ErrorCode::PaymentCreate => "err_payment_create",
+                ErrorCode::Mpi => "err_mpi",
+                ErrorCode::CurrencyNotAllowed => "err_currency_is_not_allowed",
+                ErrorCode::Look => "err_look",
+                ErrorCode::ModsEmpty => "err_mods_empty",
+                ErrorCode::PaymentType => "payment_err_type",
+                ErrorCode::PaymentCurrency => "err_payment_currency",
+                ErrorCode::PaymentExchangeRates => "err_payment_exchangerates",
+                ErrorCode::Signature => "err_signature",
+                ErrorCode::ApiAction => "err_api_action",
+                ErrorCode::ApiCallback => "err_api_callback",
+                ErrorCode::ApiIp => "err_api_ip",
+            }
+        }
+
+        /// Returns the meaning of the error code in English.
+        pub fn description(&self) -> &'static str {
+            match self {
+                ErrorCode::Auth => "Authorization is required",
+                ErrorCode::Cache => "The cached data has expired",
+                ErrorCode::UserNotFound => "The user is not found",
+                ErrorCode::SmsSend => "Failed to send the SMS",
+                ErrorCode::SmsOtp => "The SMS password is incorrect",
+                ErrorCode::ShopBlocked => "The shop is blocked",
+                ErrorCode::ShopNotActive => "The shop is not active",
+                ErrorCode::InvalidSignature => "The signature of the request is invalid",
+                ErrorCode::OrderIdEmpty => "The order identifier is empty",
+                ErrorCode::OrderIdDuplicate => "An order with the same identifier already exists",
+                ErrorCode::ShopNotAgent => "The shop is not an agent",
+                ErrorCode::CardDefaultNotFound => "The card for receiving payments is not found",
+                ErrorCode::NoCardToken => "The user has no card with the given token",
+                ErrorCode::CardLiqPayDefault => "Another card must be specified",
+                ErrorCode::CardType => "The card type is not supported",
+                ErrorCode::CardCountry => "The card country is not supported",
+                ErrorCode::LimitAmount => "The amount exceeds the limit",
+                ErrorCode::PaymentAmountLimit => "The payment amount exceeds the limit",
+                ErrorCode::AmountLimit => "The balance limit is exceeded",
+                ErrorCode::SenderCard => "Another sender card must be specified",
+                ErrorCode::PaymentProcessing => "The payment is being processed",
+                ErrorCode::PaymentDiscount => "The discount for the payment is not found",
+                ErrorCode::Wallet => "Failed to load the wallet",
+                ErrorCode::GetVerifyCode => "The card verification is required",
+                ErrorCode::VerifyCode => "The verification code is incorrect",
+                ErrorCode::WaitInfo => {
+                    "Additional information is awaited, the request must be retried later"
+                }
+                ErrorCode::Path => "The address of the request is incorrect",
+                ErrorCode::PaymentCashAcquiring => "The payment cannot be made in this shop",
+                ErrorCode::SplitAmount => "The split amounts do not match the payment amount",
+                ErrorCode::CardReceiverDefault => {
+                    "The receiver has not set a card for receiving payments"
+                }
+                ErrorCode::PaymentStatus => "The payment status is incorrect",
+                ErrorCode::PublicKeyNotFound => "The public key is not found",
+                ErrorCode::PaymentNotFound => "The payment is not found",
+                ErrorCode::PaymentNotSubscribed => "The payment is not regular",
+                ErrorCode::WrongAmountCurrency => {
+                    "The payment currency does not match the debit currency"
+                }
+                ErrorCode::AmountHold => "The amount exceeds the blocked amount",
+                ErrorCode::Access => "Access is denied",
+                ErrorCode::Blocked => "Access to the account is closed",
+                ErrorCode::Empty => "A required parameter is empty",
+                ErrorCode::EmptyPhone => "The phone number is empty",
+                ErrorCode::Missing => "A required parameter is missing",
+                ErrorCode::Wrong => "A parameter value is incorrect",
+                ErrorCode::WrongCurrency => "The currency is incorrect",
+                ErrorCode::Phone => "The phone number is incorrect",
+                ErrorCode::Card => "The card number is incorrect",
+                ErrorCode::CardBin => "The card BIN is not found",
+                ErrorCode::TerminalNotFound => "The terminal is not found",
+                ErrorCode::CommissionNotFound => "The commission is not found",
+                ErrorCode::PaymentCreate => "Failed to create the payment",
+                ErrorCode::Mpi => "Failed to verify the card",
+                ErrorCode::CurrencyNotAllowed => "The currency is not allowed",
+                ErrorCode::Look => "Failed to complete the operation",
+                ErrorCode::ModsEmpty => "Failed to complete the operation",
+                ErrorCode::PaymentType => "The payment type is incorrect",
+                ErrorCode::PaymentCurrency => {
+                    "The currency of the card or the transfer is not allowed"
+                }
+                ErrorCode::PaymentExchangeRates => "No suitable exchange rate is found",
+                ErrorCode::Signature => "The signature is incorrect",
+                ErrorCode::ApiAction => "The `action` parameter is not passed",
+                ErrorCode::ApiCallback => "The `callback` parameter is not passed",
+                ErrorCode::ApiIp => "The IP address of the merchant is not allowed",
+            }
+        }
+    }
+
+    impl FromStr for ErrorCode {
+        type Err = Error;
+
+        fn from_str(value: &str) -> StdResult<Self, Self::Err> {
+            match value {
+                "err_auth" => Ok(ErrorCode::Auth),
+                "err_cache" => Ok(ErrorCode::Cache),
+                "user_not_found" => Ok(ErrorCode::UserNotFound),
+                "err_sms_send" => Ok(ErrorCode::SmsSend),
+                "err_sms_otp" => Ok(ErrorCode::SmsOtp),
+                "shop_blocked" => Ok(ErrorCode::ShopBlocked),
+                "shop_not_active" => Ok(ErrorCode::ShopNotActive),
+                "invalid_signature" => Ok(ErrorCode::InvalidSignature),
+                "order_id_empty" => Ok(ErrorCode::OrderIdEmpty),
+                "order_id_duplicate" => Ok(ErrorCode::OrderIdDuplicate),
+                "err_shop_not_agent" => Ok(ErrorCode::ShopNotAgent),
+                "err_card_def_notfound" => Ok(ErrorCode::CardDefaultNotFound),
+                "err_no_card_token" => Ok(ErrorCode::NoCardToken),
+                "err_card_liqpay_def" => Ok(ErrorCode::CardLiqPayDefault),
+                "err_card_type" => Ok(ErrorCode::CardType),
+                "err_card_country" => Ok(ErrorCode::CardCountry),
+                "err_limit_amount" => Ok(ErrorCode::LimitAmount),
+                "err_payment_amount_limit" => Ok(ErrorCode::PaymentAmountLimit),
+                "amount_limit" => Ok(ErrorCode::AmountLimit),
+                "payment_err_sender_card" => Ok(ErrorCode::SenderCard),
+                "payment_processing" => Ok(ErrorCode::PaymentProcessing),
+                "err_payment_discount" => Ok(ErrorCode::PaymentDiscount),
+                "err_wallet" => Ok(ErrorCode::Wallet),
+                "err_get_verify_code" => Ok(ErrorCode::GetVerifyCode),
+                "err_verify_code" => Ok(ErrorCode::VerifyCode),
+                "wait_info" => Ok(ErrorCode::WaitInfo),
+                "err_path" => Ok(ErrorCode::Path),
+                "err_payment_cash_acq" => Ok(ErrorCode::PaymentCashAcquiring),
+                "err_split_amount" => Ok(ErrorCode::SplitAmount),
+                "err_card_receiver_def" => Ok(ErrorCode::CardReceiverDefault),
+                "payment_err_status" => Ok(ErrorCode::PaymentStatus),
+                "public_key_not_found" => Ok(ErrorCode::PublicKeyNotFound),
+                "payment_not_found" => Ok(ErrorCode::PaymentNotFound),
+                "payment_not_subscribed" => Ok(ErrorCode::PaymentNotSubscribed),
+                "wrong_amount_currency" => Ok(ErrorCode::WrongAmountCurrency),
+                "err_amount_hold" => Ok(ErrorCode::AmountHold),
+                "err_access" => Ok(ErrorCode::Access),
+                "err_blocked" => Ok(ErrorCode::Blocked),
+                "err_empty" => Ok(ErrorCode::Empty),
+                "err_empty_phone" => Ok(ErrorCode::EmptyPhone),
+                "err_missing" => Ok(ErrorCode::Missing),
+                "err_wrong" => Ok(ErrorCode::Wrong),
+                "err_wrong_currency" => Ok(ErrorCode::WrongCurrency),
+                "err_phone" => Ok(ErrorCode::Phone),
+                "err_card" => Ok(ErrorCode::Card),
+                "err_card_bin" => Ok(ErrorCode::CardBin),
+                "err_terminal_notfound" => Ok(ErrorCode::TerminalNotFound),
+                "err_commission_notfound" => Ok(ErrorCode::CommissionNotFound),
+                "err_payment_create" => Ok(ErrorCode::PaymentCreate),
+                "err_mpi" => Ok(ErrorCode::Mpi),
+                "err_currency_is_not_allowed" => Ok(ErrorCode::CurrencyNotAllowed),
+                "err_look" => Ok(ErrorCode::Look),
+                "err_mods_empty" => Ok(ErrorCode::ModsEmpty),
+                "payment_err_type" => Ok(ErrorCode::PaymentType),
+                "err_payment_currency" => Ok(ErrorCode::PaymentCurrency),
+                "err_payment_exchangerates" => Ok(ErrorCode::PaymentExchangeRates),
+                "err_signature" => Ok(ErrorCode::Signature),
+                "err_api_action" => Ok(ErrorCode::ApiAction),
+                "err_api_callback" => Ok(ErrorCode::ApiCallback),
+                "err_api_ip" => Ok(ErrorCode::ApiIp),
+                c => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error code {} is not supported.", c),
+                )),
+            }
+        }
+    }
+
+    impl Display for ErrorCode {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
     /// Represents a language.
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum Language {
         #[serde(rename = "en")]
         En,
@@ -181,8 +761,24 @@ pub mod enums {
         Uk,
     }
 
+    impl Language {
+        /// Returns the string representation of the language as it is sent to and received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Language::En => "en",
+                Language::Uk => "uk",
+            }
+        }
+    }
+
+    impl Display for Language {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
     /// Represents a 3D Secure status.
-    #[derive(Debug, Deserialize, Serialize)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum MpiEci {
         #[serde(rename = "5")]
         Success3Ds,
@@ -193,7 +789,7 @@ pub mod enums {
     }
 
     /// Represents a payment type.
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum PayType {
         #[serde(rename = "card")]
         Card,
@@ -225,17 +821,75 @@ pub mod enums {
         Tavv,
     }
 
-    /// Represent an operation result.
-    #[derive(Deserialize, Debug)]
+    impl PayType {
+        /// Returns the string representation of the payment type as it is sent to and received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                PayType::Card => "card",
+                PayType::LiqPay => "liqpay",
+                PayType::Privat24 => "privat24",
+                PayType::Masterpass => "masterpass",
+                PayType::MomentPart => "moment_part",
+                PayType::PayPart => "paypart",
+                PayType::Cash => "cash",
+                PayType::Invoice => "invoice",
+                PayType::QR => "qr",
+                PayType::ApplePay => "apay",
+                PayType::GooglePay => "gpay",
+                PayType::ApplePayDecrypted => "apay_tavv",
+                PayType::GooglePayDecrypted => "gpay_tavv",
+                PayType::Tavv => "tavv",
+            }
+        }
+
+        /// Indicates whether the payment is made by a card directly or by its token.
+        pub fn is_card(&self) -> bool {
+            matches!(self, PayType::Card | PayType::Tavv)
+        }
+
+        /// Indicates whether the payment is made by a wallet, e.g. Apple Pay, Google Pay or Privat24.
+        pub fn is_wallet(&self) -> bool {
+            matches!(
+                self,
+                PayType::LiqPay
+                    | PayType::Privat24
+                    | PayType::Masterpass
+                    | PayType::ApplePay
+                    | PayType::GooglePay
+                    | PayType::ApplePayDecrypted
+                    | PayType::GooglePayDecrypted
+            )
+        }
+
+        /// Indicates whether the payment is made in cash.
+        pub fn is_cash(&self) -> bool {
+            matches!(self, PayType::Cash)
+        }
+
+        /// Indicates whether the payment is made by scanning a QR code.
+        pub fn is_qr(&self) -> bool {
+            matches!(self, PayType::QR)
+        }
+    }
+
+    impl Display for PayType {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
+    /// Represent an operation result. Some endpoints report a successful result as `success`,
+    /// which is deserialized as `Ok` as well.
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum Result {
-        #[serde(rename = "ok")]
+        #[serde(rename = "ok", alias = "success")]
         Ok,
         #[serde(rename = "error")]
         Error,
     }
 
     /// Represents an operation status.
-    #[derive(Deserialize, Debug)]
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
     pub enum Status {
         #[serde(rename = "error")]
         Error,
@@ -245,11 +899,11 @@ pub mod enums {
         Reversed,
         #[serde(rename = "success")]
         Success,
-        #[serde(rename = "3ds_verify ")]
+        #[serde(rename = "3ds_verify")]
         Verify3Ds,
-        #[serde(rename = "cvv_verify ")]
+        #[serde(rename = "cvv_verify")]
         VerifyCvv,
-        #[serde(rename = "otp_verify ")]
+        #[serde(rename = "otp_verify")]
         VerifyOtp,
         #[serde(rename = "ivr_verify")]
         VerifyIvr,
@@ -259,27 +913,27 @@ pub mod enums {
         VerifyPhone,
         #[serde(rename = "pin_verify")]
         VerifyPin,
-        #[serde(rename = "receiver_verify ")]
+        #[serde(rename = "receiver_verify")]
         VerifyReceiver,
-        #[serde(rename = "sender_verify ")]
+        #[serde(rename = "sender_verify")]
         VerifySender,
-        #[serde(rename = "senderapp_verify ")]
+        #[serde(rename = "senderapp_verify")]
         VerifySenderApp,
         #[serde(rename = "captcha_verify")]
         VerifyCaptcha,
         #[serde(rename = "mp_verify")]
         VerifyMasterPass,
-        #[serde(rename = "wait_accept ")]
+        #[serde(rename = "wait_accept")]
         WaitAccept,
-        #[serde(rename = "wait_card ")]
+        #[serde(rename = "wait_card")]
         WaitCard,
-        #[serde(rename = "wait_compensation ")]
+        #[serde(rename = "wait_compensation")]
         WaitCompensation,
-        #[serde(rename = "wait_lc ")]
+        #[serde(rename = "wait_lc")]
         WaitLc,
-        #[serde(rename = "wait_reserve ")]
+        #[serde(rename = "wait_reserve")]
         WaitReserve,
-        #[serde(rename = "wait_secure ")]
+        #[serde(rename = "wait_secure")]
         WaitSecure,
         #[serde(rename = "wait_qr")]
         WaitQr,
@@ -305,6 +959,117 @@ pub mod enums {
         Active,
     }
 
+    impl Status {
+        /// Returns the string representation of the status as it is sent to and received from LiqPay.
+        pub fn as_str(&self) -> &'static str {
+            match self {
+                Status::Error => "error",
+                Status::Failure => "failure",
+                Status::Reversed => "reversed",
+                Status::Success => "success",
+                Status::Verify3Ds => "3ds_verify",
+                Status::VerifyCvv => "cvv_verify",
+                Status::VerifyOtp => "otp_verify",
+                Status::VerifyIvr => "ivr_verify",
+                Status::VerifyPassword => "password_verify",
+                Status::VerifyPhone => "phone_verify",
+                Status::VerifyPin => "pin_verify",
+                Status::VerifyReceiver => "receiver_verify",
+                Status::VerifySender => "sender_verify",
+                Status::VerifySenderApp => "senderapp_verify",
+                Status::VerifyCaptcha => "captcha_verify",
+                Status::VerifyMasterPass => "mp_verify",
+                Status::WaitAccept => "wait_accept",
+                Status::WaitCard => "wait_card",
+                Status::WaitCompensation => "wait_compensation",
+                Status::WaitLc => "wait_lc",
+                Status::WaitReserve => "wait_reserve",
+                Status::WaitSecure => "wait_secure",
+                Status::WaitQr => "wait_qr",
+                Status::WaitSender => "wait_sender",
+                Status::WaitCash => "cash_wait",
+                Status::WaitHold => "hold_wait",
+                Status::WaitInvoice => "invoice_wait",
+                Status::Subscribed => "subscribed",
+                Status::Unsubscribed => "unsubscribed",
+                Status::Prepared => "prepared",
+                Status::Processing => "processing",
+                Status::TryAgain => "try_again",
+                Status::Active => "active",
+            }
+        }
+    }
+
+    impl Status {
+        /// Indicates whether the operation has reached a terminal state and will not change anymore.
+        /// An active subscription is not final, as it keeps charging the customer until it is
+        /// unsubscribed, and neither is `try_again`, as the operation may still succeed once retried.
+        pub fn is_final(&self) -> bool {
+            matches!(
+                self,
+                Status::Error
+                    | Status::Failure
+                    | Status::Reversed
+                    | Status::Success
+                    | Status::Unsubscribed
+            )
+        }
+
+        /// Indicates whether the operation awaits an action from a customer or a merchant,
+        /// e.g. a verification, a payment by a QR code or an invoice.
+        pub fn requires_action(&self) -> bool {
+            matches!(
+                self,
+                Status::Verify3Ds
+                    | Status::VerifyCvv
+                    | Status::VerifyOtp
+                    | Status::VerifyIvr
+                    | Status::VerifyPassword
+                    | Status::VerifyPhone
+                    | Status::VerifyPin
+                    | Status::VerifyReceiver
+                    | Status::VerifySender
+                    | Status::VerifySenderApp
+                    | Status::VerifyCaptcha
+                    | Status::VerifyMasterPass
+                    | Status::WaitCard
+                    | Status::WaitQr
+                    | Status::WaitSender
+                    | Status::WaitCash
+                    | Status::WaitInvoice
+                    | Status::Prepared
+            )
+        }
+
+        /// Indicates whether the operation has been softly declined and may be retried.
+        /// A retry is a new payment attempt with the same `order_id`. A retryable status is not final.
+        pub fn is_retryable(&self) -> bool {
+            matches!(self, Status::TryAgain)
+        }
+
+        /// Indicates whether a subscription is active, i.e. it has been set up and keeps charging
+        /// the customer until it is unsubscribed.
+        pub fn is_active_subscription(&self) -> bool {
+            matches!(self, Status::Subscribed | Status::Active)
+        }
+
+        /// Indicates whether the operation is being processed on LiqPay's side.
+        /// A status is exactly one of final, retryable, requiring an action, an active subscription
+        /// or pending.
+        pub fn is_pending(&self) -> bool {
+            !self.is_final()
+                && !self.is_retryable()
+                && !self.requires_action()
+                && !self.is_active_subscription()
+        }
+    }
+
+    impl Display for Status {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(self.as_str())
+        }
+    }
+
     /// Represents a preparation status.
     #[derive(Debug, Serialize)]
     pub enum Prepare {
@@ -314,3 +1079,1637 @@ pub mod enums {
         Tariffs,
     }
 }
+
+pub mod amount {
+    use serde::{Serialize, Serializer};
+
+    use crate::common::enums::{Currency, Language};
+
+    /// The number of decimal places, an amount is rounded to when it is serialized.
+    /// It is the number of decimal places of the minor unit of every currency supported by LiqPay.
+    const SERIALIZED_DECIMAL_PLACES: u32 = 2;
+
+    /// Represents a monetary amount in the major units of a currency.
+    /// An amount is rounded half away from zero to two decimal places when it is sent to LiqPay,
+    /// e.g. `3.335` is sent as `3.34`.
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+    pub struct Amount(f64);
+
+    impl Amount {
+        /// Constructs a new amount in the major units of a currency, e.g. `19.99`.
+        pub fn new(value: f64) -> Self {
+            Self(value)
+        }
+
+        /// Constructs a new amount from the minor units of a currency, e.g. `1999` cents.
+        pub fn from_minor(units: i64, currency: Currency) -> Self {
+            Self(units as f64 / minor_units_factor(currency))
+        }
+
+        /// Converts the amount to the minor units of a currency.
+        pub fn to_minor(&self, currency: Currency) -> i64 {
+            (self.0 * minor_units_factor(currency)).round() as i64
+        }
+
+        /// Returns the amount in the major units of a currency.
+        pub fn value(&self) -> f64 {
+            self.0
+        }
+
+        /// Rounds the amount half away from zero to the decimal places of a currency,
+        /// e.g. `3.335` is rounded to `3.34` for UAH.
+        pub fn round(&self, currency: Currency) -> Self {
+            Self(round_to(self.0, currency.decimal_places()))
+        }
+
+        /// Returns the value, which is sent to LiqPay, i.e. rounded to two decimal places.
+        pub(crate) fn serialized_value(&self) -> f64 {
+            round_to(self.0, SERIALIZED_DECIMAL_PLACES)
+        }
+
+        /// Formats the amount for humans, e.g. for receipts, with the digits grouped by thousands
+        /// and the currency symbol placed according to `locale`: `1 234,56 ₴` for Ukrainian
+        /// and `$1,234.56` for English. The serialized amount is not affected.
+        pub fn format(&self, currency: Currency, locale: Language) -> String {
+            let (group_separator, decimal_separator) = match locale {
+                Language::Uk => (' ', ','),
+                Language::En => (',', '.'),
+            };
+
+            let decimal_places = currency.decimal_places() as usize;
+            let minor_units = self.to_minor(currency);
+            let digits = format!(
+                "{:0>width$}",
+                minor_units.unsigned_abs(),
+                width = decimal_places + 1
+            );
+            let (major, minor) = digits.split_at(digits.len() - decimal_places);
+
+            let mut number = String::new();
+
+            for (index, digit) in major.chars().enumerate() {
+                if index > 0 && (major.len() - index) % 3 == 0 {
+                    number.push(group_separator);
+                }
+
+                number.push(digit);
+            }
+
+            if !minor.is_empty() {
+                number.push(decimal_separator);
+                number.push_str(minor);
+            }
+
+            let sign = if minor_units < 0 { "-" } else { "" };
+
+            match locale {
+                Language::Uk => format!("{}{} {}", sign, number, currency.symbol()),
+                Language::En => format!("{}{}{}", sign, currency.symbol(), number),
+            }
+        }
+    }
+
+    impl Serialize for Amount {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_f64(self.serialized_value())
+        }
+    }
+
+    impl From<f64> for Amount {
+        fn from(value: f64) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Amount> for f64 {
+        fn from(amount: Amount) -> Self {
+            amount.0
+        }
+    }
+
+    /// Represents an amount paired with its currency, so that the two cannot disagree.
+    /// Request constructors such as `CardPaymentRequest::with_value` accept it, as does `(amount, currency)`.
+    /// Serializes to the `amount` and `currency` fields.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+    pub struct MonetaryValue {
+        amount: Amount,
+        currency: Currency,
+    }
+
+    impl MonetaryValue {
+        /// Constructs a new monetary value.
+        pub fn new(amount: impl Into<Amount>, currency: Currency) -> Self {
+            Self {
+                amount: amount.into(),
+                currency,
+            }
+        }
+
+        /// Constructs a new monetary value from the minor units of a currency, e.g. `1999` cents.
+        pub fn from_minor(units: i64, currency: Currency) -> Self {
+            Self::new(Amount::from_minor(units, currency), currency)
+        }
+
+        /// Returns the amount.
+        pub fn amount(&self) -> Amount {
+            self.amount
+        }
+
+        /// Returns the currency.
+        pub fn currency(&self) -> Currency {
+            self.currency
+        }
+    }
+
+    impl<T: Into<Amount>> From<(T, Currency)> for MonetaryValue {
+        fn from((amount, currency): (T, Currency)) -> Self {
+            Self::new(amount, currency)
+        }
+    }
+
+    fn minor_units_factor(currency: Currency) -> f64 {
+        10_f64.powi(currency.decimal_places() as i32)
+    }
+
+    /// Rounds a value half away from zero. The scaled value is rounded to six extra decimal places first,
+    /// so that a value like `3.335`, which is stored as `3.33499...`, is rounded up.
+    fn round_to(value: f64, decimal_places: u32) -> f64 {
+        let factor = 10_f64.powi(decimal_places as i32);
+        let scaled = ((value * factor) * 1e6).round() / 1e6;
+
+        scaled.round() / factor
+    }
+}
+
+pub mod card {
+    use std::fmt::{self, Debug, Formatter};
+
+    use serde::Serialize;
+
+    /// The number of the last digits of a card number, which are left visible when it is masked.
+    const VISIBLE_DIGITS: usize = 4;
+
+    /// Replaces the hidden digits of a masked card number.
+    const MASK: &str = "****";
+
+    /// Masks a card number leaving only its last 4 digits visible.
+    /// A value of 4 digits or fewer is masked entirely, as it would otherwise be shown in full.
+    pub(crate) fn mask_card_number(card: &str) -> String {
+        let digits: Vec<char> = card.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if digits.len() <= VISIBLE_DIGITS {
+            return MASK.to_owned();
+        }
+
+        let last_digits: String = digits[digits.len() - VISIBLE_DIGITS..].iter().collect();
+
+        format!("{}{}", MASK, last_digits)
+    }
+
+    /// Represents a payment card brand.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum CardBrand {
+        Visa,
+        MasterCard,
+        Maestro,
+        AmericanExpress,
+        DinersClub,
+        Discover,
+        Jcb,
+        UnionPay,
+        Prostir,
+    }
+
+    impl CardBrand {
+        /// Detects the brand of a card by its number using the issuer identification number ranges.
+        /// Spaces and dashes are ignored. Returns `None` if the brand is unknown.
+        pub fn detect(pan: &str) -> Option<Self> {
+            let digits: String = pan.chars().filter(|c| *c != ' ' && *c != '-').collect();
+
+            if digits.len() < 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+
+            let prefix = |length: usize| digits.get(..length).and_then(|p| p.parse::<u32>().ok());
+            let in_range = |length: usize, from: u32, to: u32| {
+                prefix(length).is_some_and(|p| (from..=to).contains(&p))
+            };
+
+            if in_range(4, 9804, 9804) {
+                Some(Self::Prostir)
+            } else if in_range(1, 4, 4) {
+                Some(Self::Visa)
+            } else if in_range(2, 51, 55) || in_range(4, 2221, 2720) {
+                Some(Self::MasterCard)
+            } else if in_range(2, 34, 34) || in_range(2, 37, 37) {
+                Some(Self::AmericanExpress)
+            } else if in_range(3, 300, 305) || in_range(2, 36, 36) || in_range(2, 38, 39) {
+                Some(Self::DinersClub)
+            } else if in_range(4, 3528, 3589) {
+                Some(Self::Jcb)
+            } else if in_range(4, 6011, 6011) || in_range(3, 644, 649) || in_range(2, 65, 65) {
+                Some(Self::Discover)
+            } else if in_range(2, 62, 62) {
+                Some(Self::UnionPay)
+            } else if in_range(2, 50, 50) || in_range(2, 56, 69) {
+                Some(Self::Maestro)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Represents a card number, which is masked in the debug output.
+    #[derive(Clone, PartialEq, Serialize)]
+    #[serde(transparent)]
+    pub(crate) struct CardNumber(String);
+
+    impl From<String> for CardNumber {
+        fn from(card: String) -> Self {
+            Self(card)
+        }
+    }
+
+    impl Debug for CardNumber {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(&mask_card_number(&self.0))
+        }
+    }
+
+    /// Represents a card CVV/CVV2 code, which is hidden in the debug output.
+    #[derive(Clone, PartialEq, Serialize)]
+    #[serde(transparent)]
+    pub(crate) struct CardCvv(String);
+
+    impl From<String> for CardCvv {
+        fn from(cvv: String) -> Self {
+            Self(cvv)
+        }
+    }
+
+    impl Debug for CardCvv {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("***")
+        }
+    }
+}
+
+pub mod order {
+    use std::fmt::{self, Display, Formatter};
+
+    use uuid::Uuid;
+
+    use crate::common::validation::ORDER_ID_MAX_LENGTH;
+
+    /// Represents a unique identifier of an order.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct OrderId(String);
+
+    impl OrderId {
+        /// Generates a random order identifier, which consists of `prefix` followed by a UUID.
+        /// The prefix is truncated so that the identifier never exceeds the allowed length.
+        pub fn random(prefix: &str) -> Self {
+            let uuid = Uuid::new_v4().simple().to_string();
+            let prefix: String = prefix
+                .chars()
+                .take(ORDER_ID_MAX_LENGTH - uuid.len())
+                .collect();
+
+            Self(format!("{}{}", prefix, uuid))
+        }
+
+        /// Returns the order identifier as a string slice.
+        pub fn as_str(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl From<OrderId> for String {
+        fn from(order_id: OrderId) -> Self {
+            order_id.0
+        }
+    }
+
+    impl Display for OrderId {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.0)
+        }
+    }
+}
+
+pub mod validation {
+    use crate::common::amount::Amount;
+    use crate::error::LiqPayError;
+
+    /// Validates an IBAN using the ISO 13616 mod-97 checksum. Spaces are ignored.
+    pub fn validate_iban(iban: &str) -> bool {
+        let iban: String = iban
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        if !(15..=34).contains(&iban.len()) || !iban.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return false;
+        }
+
+        let (country_code, check_digits) = iban.split_at(2);
+
+        if !country_code.chars().all(|c| c.is_ascii_alphabetic())
+            || !check_digits[..2].chars().all(|c| c.is_ascii_digit())
+        {
+            return false;
+        }
+
+        let (head, tail) = iban.split_at(4);
+        let remainder = tail.chars().chain(head.chars()).fold(0, |remainder, c| {
+            let value = c.to_digit(36).unwrap_or_default();
+            let shift = if value < 10 { 10 } else { 100 };
+
+            (remainder * shift + value) % 97
+        });
+
+        remainder == 1
+    }
+
+    /// Validates the format of an email address. Only the overall shape is checked:
+    /// a non-empty local part and a dotted domain separated by a single `@`.
+    pub fn validate_email(email: &str) -> bool {
+        let Some((local, domain)) = email.split_once('@') else {
+            return false;
+        };
+
+        !local.is_empty()
+            && !domain.contains('@')
+            && !email.chars().any(char::is_whitespace)
+            && domain.contains('.')
+            && domain.split('.').all(|label| !label.is_empty())
+    }
+
+    /// Validates the format of a URL. Only the overall shape is checked: a scheme, e.g. `https`,
+    /// followed by `://` and a non-empty remainder without whitespace.
+    pub fn validate_url(url: &str) -> bool {
+        let Some((scheme, rest)) = url.split_once("://") else {
+            return false;
+        };
+
+        let mut scheme_chars = scheme.chars();
+
+        scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+            && !rest.is_empty()
+            && !rest.chars().any(char::is_whitespace)
+    }
+
+    /// The maximum length of an order identifier.
+    pub const ORDER_ID_MAX_LENGTH: usize = 255;
+
+    /// The maximum length of a payment description.
+    pub const DESCRIPTION_MAX_LENGTH: usize = 2048;
+
+    /// The maximum length of an invoice description.
+    pub const INVOICE_DESCRIPTION_MAX_LENGTH: usize = 1000;
+
+    /// The maximum length of a product category.
+    pub const PRODUCT_CATEGORY_MAX_LENGTH: usize = 25;
+
+    /// The maximum length of a customer identifier.
+    pub const CUSTOMER_MAX_LENGTH: usize = 100;
+
+    pub(crate) fn check_order_id(order_id: &str) -> Result<(), LiqPayError> {
+        check_max_length("order_id", order_id, ORDER_ID_MAX_LENGTH)
+    }
+
+    pub(crate) fn check_customer(customer: Option<&str>) -> Result<(), LiqPayError> {
+        match customer {
+            Some(customer) => check_max_length("customer", customer, CUSTOMER_MAX_LENGTH),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn check_max_length(
+        field: &str,
+        value: &str,
+        max_length: usize,
+    ) -> Result<(), LiqPayError> {
+        if value.chars().count() <= max_length {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` must not exceed {} characters",
+                field, max_length
+            )))
+        }
+    }
+
+    pub(crate) fn check_not_blank(field: &str, value: &str) -> Result<(), LiqPayError> {
+        if value.trim().is_empty() {
+            Err(LiqPayError::Validation(format!(
+                "`{}` must not be empty",
+                field
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Validates that an amount is positive.
+    pub(crate) fn check_positive_amount(field: &str, amount: Amount) -> Result<(), LiqPayError> {
+        if amount.value() > 0.0 {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` must be a positive amount",
+                field
+            )))
+        }
+    }
+
+    /// Validates a UTC date and time in the YYYY-MM-DD HH:MM:SS format.
+    pub(crate) fn check_date_time(field: &str, value: &str) -> Result<(), LiqPayError> {
+        if is_valid_date_time(value) {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` must be a date and time in the YYYY-MM-DD HH:MM:SS format",
+                field
+            )))
+        }
+    }
+
+    fn is_valid_date_time(value: &str) -> bool {
+        let bytes = value.as_bytes();
+
+        if bytes.len() != 19 {
+            return false;
+        }
+
+        let separators_valid = bytes[4] == b'-'
+            && bytes[7] == b'-'
+            && bytes[10] == b' '
+            && bytes[13] == b':'
+            && bytes[16] == b':';
+
+        let number = |from: usize, to: usize| -> Option<u32> {
+            let part = value.get(from..to)?;
+
+            if part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse().ok()
+            } else {
+                None
+            }
+        };
+
+        let parts = (
+            number(0, 4),
+            number(5, 7),
+            number(8, 10),
+            number(11, 13),
+            number(14, 16),
+            number(17, 19),
+        );
+
+        let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = parts
+        else {
+            return false;
+        };
+
+        let is_leap_year = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year => 29,
+            2 => 28,
+            _ => return false,
+        };
+
+        separators_valid
+            && (1..=days_in_month).contains(&day)
+            && hour < 24
+            && minute < 60
+            && second < 60
+    }
+
+    pub(crate) fn check_email(field: &str, email: &str) -> Result<(), LiqPayError> {
+        if validate_email(email) {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` contains an invalid email `{}`",
+                field, email
+            )))
+        }
+    }
+
+    pub(crate) fn check_url(field: &str, url: &str) -> Result<(), LiqPayError> {
+        if validate_url(url) {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` is not a valid URL",
+                field
+            )))
+        }
+    }
+
+    pub(crate) fn check_iban(field: &str, iban: &str) -> Result<(), LiqPayError> {
+        if validate_iban(iban) {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "`{}` is not a valid IBAN",
+                field
+            )))
+        }
+    }
+}
+
+pub(crate) mod de {
+    use serde::de::{Error, Unexpected};
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(f64),
+        String(String),
+    }
+
+    /// Deserializes an optional monetary value, which LiqPay sends either as a JSON number
+    /// or as a numeric string, e.g. `"10.50"`.
+    pub(crate) fn optional_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<NumberOrString>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(NumberOrString::Number(value)) => Ok(Some(value)),
+            Some(NumberOrString::String(value)) => value.trim().parse().map(Some).map_err(|_| {
+                D::Error::invalid_value(Unexpected::Str(&value), &"a number or a numeric string")
+            }),
+        }
+    }
+
+    /// Deserializes an optional boolean, which LiqPay sends either as a JSON boolean
+    /// or as a string, e.g. `"true"`.
+    pub(crate) fn optional_bool<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<BoolOrString>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(BoolOrString::Bool(value)) => Ok(Some(value)),
+            Some(BoolOrString::String(value)) => match value.as_str() {
+                "true" | "1" => Ok(Some(true)),
+                "false" | "0" => Ok(Some(false)),
+                _ => Err(D::Error::invalid_value(
+                    Unexpected::Str(&value),
+                    &"a boolean or a boolean string",
+                )),
+            },
+        }
+    }
+
+    /// (De)serializes the masked sender card number, which LiqPay sends as `sender_card_mask2`
+    /// and, in some responses, as `sender_card_mask`. If both keys are present, the canonical one wins
+    /// instead of failing with a duplicate field. Used with `#[serde(flatten, with = "...")]`.
+    pub(crate) mod sender_card_mask {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Deserialize)]
+        struct Keys {
+            sender_card_mask2: Option<String>,
+            sender_card_mask: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Canonical<'a> {
+            sender_card_mask2: &'a Option<String>,
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keys = Keys::deserialize(deserializer)?;
+
+            Ok(keys.sender_card_mask2.or(keys.sender_card_mask))
+        }
+
+        pub(crate) fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Canonical {
+                sender_card_mask2: value,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    /// (De)serializes the QR code of a dynamic QR code payment, which LiqPay sends as `qr_code`
+    /// and, in some responses, as `qrdata`. If both keys are present, the canonical one wins
+    /// instead of failing with a duplicate field. Used with `#[serde(flatten, with = "...")]`.
+    pub(crate) mod qr_code {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Deserialize)]
+        struct Keys {
+            qr_code: Option<String>,
+            qrdata: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Canonical<'a> {
+            qr_code: &'a Option<String>,
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keys = Keys::deserialize(deserializer)?;
+
+            Ok(keys.qr_code.or(keys.qrdata))
+        }
+
+        pub(crate) fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Canonical { qr_code: value }.serialize(serializer)
+        }
+    }
+
+    /// (De)serializes the QR code of a static QR code payment, which LiqPay sends as `qrdata`
+    /// and, in some responses, as `qr_code`. If both keys are present, the canonical one wins
+    /// instead of failing with a duplicate field. Used with `#[serde(flatten, with = "...")]`.
+    pub(crate) mod qrdata {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Deserialize)]
+        struct Keys {
+            qrdata: Option<String>,
+            qr_code: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Canonical<'a> {
+            qrdata: &'a Option<String>,
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keys = Keys::deserialize(deserializer)?;
+
+            Ok(keys.qrdata.or(keys.qr_code))
+        }
+
+        pub(crate) fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Canonical { qrdata: value }.serialize(serializer)
+        }
+    }
+
+    /// (De)serializes the ACS authentication parameter, which LiqPay sends as `mpi_req_pareq`
+    /// and, in some responses, as `mpi_re1q_pareq`. If both keys are present, the canonical one wins
+    /// instead of failing with a duplicate field. Used with `#[serde(flatten, with = "...")]`.
+    pub(crate) mod mpi_req_pareq {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        #[derive(Deserialize)]
+        struct Keys {
+            mpi_req_pareq: Option<String>,
+            mpi_re1q_pareq: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct Canonical<'a> {
+            mpi_req_pareq: &'a Option<String>,
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let keys = Keys::deserialize(deserializer)?;
+
+            Ok(keys.mpi_req_pareq.or(keys.mpi_re1q_pareq))
+        }
+
+        pub(crate) fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Canonical {
+                mpi_req_pareq: value,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+pub mod responses {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    use crate::common::enums::{
+        Action, Bonus, Currency, ErrorCode, Language, MpiEci, PayType, Result, Status, Version,
+    };
+
+    /// Returns the description of an error, i.e. `err_description` if it is not empty, otherwise
+    /// the English meaning of `err_code`, or the code itself if its meaning is unknown.
+    pub(crate) fn localized_error(
+        error_code: Option<&str>,
+        error_description: Option<&str>,
+    ) -> Option<String> {
+        if let Some(description) =
+            error_description.filter(|description| !description.trim().is_empty())
+        {
+            return Some(description.to_owned());
+        }
+
+        let error_code = error_code?;
+
+        let description = error_code
+            .parse::<ErrorCode>()
+            .map(|code| code.description().to_owned())
+            .unwrap_or_else(|_| error_code.to_owned());
+
+        Some(description)
+    }
+
+    /// Represents the fields shared by the responses to payment operations.
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    pub struct PaymentResponse {
+        /// Represents the result of the request. Can be either `ok` or `error`.
+        pub result: Result,
+        /// Represents the status of the request. Possible values are `error` - incorrect data,
+        /// `failure` - payment failed, `reversed` - payment refunded, `success` - successful payment,
+        /// `subscribed` - successful subscription, `3ds_verify` - the verification by 3DS is required,
+        /// `cvv_verify` - CVV required, `otp_verify` - confirmation by OTP is required,
+        /// `receiver_verify` - additional receiver information is required,
+        /// `sender_verify` - additional sender information is required,
+        /// `hold_wait` - successful blocking of the amount on the sender's account,
+        /// `wait_accept` - the store is not verified at this point, but the money are withdrawn from a client,
+        /// `wait_secure` - verified payment.
+        pub status: Status,
+        /// Represents the identifier of an acquirer.
+        #[serde(rename = "acq_id")]
+        pub acquirer_id: Option<u32>,
+        /// Represents the operation type. Possible values are `pay` - payment,
+        /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation,
+        /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
+        pub action: Option<Action>,
+        /// Represents the agent commission.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub agent_commission: Option<f64>,
+        /// Represents the payment amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub amount: Option<f64>,
+        /// Represents the payer bonus amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub amount_bonus: Option<f64>,
+        /// Represents the payment credit amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub amount_credit: Option<f64>,
+        /// Represents the payment debit amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub amount_debit: Option<f64>,
+        /// Represents the authorization code for credit.
+        pub authcode_credit: Option<String>,
+        /// Represents the authorization code for debit.
+        pub authcode_debit: Option<String>,
+        /// Represents the bonus percentage.
+        #[serde(rename = "bonus_procent")]
+        pub bonus_percent: Option<f32>,
+        /// Represents the bonus type. Possible values are `bonusplus`, `personal`, `promo` and `discount_club`.
+        pub bonus_type: Option<Bonus>,
+        /// Represents the sender's card token.
+        pub card_token: Option<String>,
+        /// Represents the commission charged to credit.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub commission_credit: Option<f64>,
+        /// Represents the commission charged to debit.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub commission_debit: Option<f64>,
+        /// Represents the payment creation date.
+        #[serde(rename = "create_date")]
+        pub creation_date: Option<u64>,
+        /// Represents the currency of the payment.
+        pub currency: Option<String>,
+        /// Represents the currency used for credit.
+        pub currency_credit: Option<Currency>,
+        /// Represents the currency used for debit.
+        pub currency_debit: Option<Currency>,
+        /// Represents the payment description.
+        pub description: Option<String>,
+        /// Represents the end date of the payment.
+        pub end_date: Option<u64>,
+        /// Represents the IP address of a sender.
+        pub ip: Option<String>,
+        /// Indicates whether a transaction passed with 3DS.
+        #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
+        pub is_3ds: Option<bool>,
+        /// Represents the Id of an order in the LiqPay system.
+        pub liqpay_order_id: Option<String>,
+        /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
+        /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
+        pub mpi_eci: Option<MpiEci>,
+        /// Represents ACS value after authentication, returned by the issuer.
+        pub mpi_cres: Option<String>,
+        /// Represents the identifier of an order.
+        pub order_id: Option<String>,
+        /// Represents the identifier of a payment.
+        pub payment_id: Option<u64>,
+        /// Represents the payment type. Possible values are `card` - paid by card,
+        /// `privat24` - account in the Privat24 system, `moment_part` - installment,
+        /// `cash` - paid in cash, `invoice` - invoiced to an email,
+        /// `qr` - paid by scanning a QR code.
+        #[serde(rename = "paytype")]
+        pub pay_type: Option<PayType>,
+        /// Represents the public key of the shop.
+        pub public_key: Option<String>,
+        /// Represents the receiver's commission.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub receiver_commission: Option<f64>,
+        /// Represents the identifier of the transaction in a the issuer bank's system for credit.
+        #[serde(rename = "rrn_credit")]
+        pub retrieval_reference_number_credit: Option<String>,
+        /// Represents the identifier of the transaction in a the issuer bank's system for debit.
+        #[serde(rename = "rrn_debit")]
+        pub retrieval_reference_number_debit: Option<String>,
+        /// Represents the sender's bonus amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub sender_bonus: Option<f64>,
+        /// Represents the sender's bank.
+        pub sender_card_bank: Option<String>,
+        /// Represents the sender's card country code in the ISO-3166 format.
+        pub sender_card_country: Option<u16>,
+        /// Represents the masked sender card number.
+        #[serde(flatten, with = "crate::common::de::sender_card_mask")]
+        pub sender_card_mask: Option<String>,
+        /// Represents the sender's card type - either VISA or MasterCard.
+        pub sender_card_type: Option<String>,
+        /// Represents the sender's commission amount.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub sender_commission: Option<f64>,
+        /// Represents the sender's first name.
+        pub sender_first_name: Option<String>,
+        /// Represents the sender's last name.
+        pub sender_last_name: Option<String>,
+        /// Represents the sender's phone number.
+        pub sender_phone: Option<String>,
+        /// Represents the identifier of a transaction in LiqPay.
+        pub transaction_id: Option<u64>,
+        /// Represents the type of an operation.
+        #[serde(rename = "type")]
+        pub operation_type: Option<String>,
+        /// Represents the API version.
+        pub version: Option<Version>,
+        /// Represents the language of the payment.
+        pub language: Option<Language>,
+        /// Represents the phone number used for confirmation via a one-time password.
+        pub confirm_phone: Option<String>,
+        /// Represents the simulated split of the payment, which LiqPay returns instead of dividing
+        /// the funds when `split_tickets_only` is set. Either a JSON array or a string holding one.
+        pub split_tickets: Option<Value>,
+        /// Holds an error code.
+        #[serde(rename = "err_code")]
+        pub error_code: Option<String>,
+        /// Holds an error description.
+        #[serde(rename = "err_description")]
+        pub error_description: Option<String>,
+        /// Holds the fields, which are not modeled by the response.
+        #[serde(flatten)]
+        pub extra: BTreeMap<String, Value>,
+    }
+
+    impl PaymentResponse {
+        /// Returns the description of the error, if any. LiqPay sends `err_description` in the `language`
+        /// of the request, so the description is localized when the request sets a language.
+        /// If the description is empty, the English meaning of `err_code` is returned instead.
+        pub fn error_localized(&self) -> Option<String> {
+            localized_error(
+                self.error_code.as_deref(),
+                self.error_description.as_deref(),
+            )
+        }
+
+        /// Indicates whether the split of the payment has only been simulated with `split_tickets_only`,
+        /// i.e. no funds have been divided among the receivers.
+        pub fn was_simulated(&self) -> bool {
+            self.split_tickets.is_some()
+        }
+
+        /// Parses the simulated split of the payment. Returns `None` if the split is absent
+        /// or is not a valid list of tickets.
+        pub fn simulated_split(&self) -> Option<Vec<SplitTicket>> {
+            match self.split_tickets.as_ref()? {
+                Value::String(tickets) => serde_json::from_str(tickets).ok(),
+                tickets => serde_json::from_value(tickets.clone()).ok(),
+            }
+        }
+    }
+
+    /// Represents the share of a receiver in a simulated split of a payment.
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    pub struct SplitTicket {
+        /// Represents the public key of the receiver's shop.
+        pub public_key: Option<String>,
+        /// Represents the amount allocated to the receiver.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub amount: Option<f64>,
+        /// Represents the commission charged to the receiver.
+        #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+        pub commission: Option<f64>,
+        /// Represents the party paying the commission. Possible values are `sender` and `receiver`.
+        pub commission_payer: Option<String>,
+        /// Holds the fields, which are not modeled by the ticket.
+        #[serde(flatten)]
+        pub extra: BTreeMap<String, Value>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+
+    use serde::Serialize;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    use crate::common::amount::{Amount, MonetaryValue};
+    use crate::common::card::{CardBrand, mask_card_number};
+    use crate::common::enums::{
+        Action, Currency, Language, PayType, Prepare, Result, Status, Version,
+    };
+    use crate::common::order::OrderId;
+    use crate::common::responses::{PaymentResponse, SplitTicket};
+    use crate::common::validation::{ORDER_ID_MAX_LENGTH, check_order_id, validate_iban};
+    use crate::informational::status::StatusRequest;
+    use crate::internet_acquiring::card::CardPaymentResponse;
+    use crate::internet_acquiring::cash::CashPaymentRequest;
+    use crate::internet_acquiring::qr_code::StaticQrCodeResponse;
+    use crate::internet_acquiring::subscription::SubscribeResponse;
+    use crate::internet_acquiring::token::TokenPaymentResponse;
+    use crate::internet_acquiring::two_step::FundsBlockingResponse;
+    use crate::verification::otp::OtpResponse;
+
+    #[derive(Debug, PartialEq)]
+    enum Category {
+        Final,
+        Retryable,
+        Action,
+        Subscription,
+        Pending,
+    }
+
+    const STATUS_CATEGORIES: [(Status, Category); 33] = [
+        (Status::Error, Category::Final),
+        (Status::Failure, Category::Final),
+        (Status::Reversed, Category::Final),
+        (Status::Success, Category::Final),
+        (Status::Verify3Ds, Category::Action),
+        (Status::VerifyCvv, Category::Action),
+        (Status::VerifyOtp, Category::Action),
+        (Status::VerifyIvr, Category::Action),
+        (Status::VerifyPassword, Category::Action),
+        (Status::VerifyPhone, Category::Action),
+        (Status::VerifyPin, Category::Action),
+        (Status::VerifyReceiver, Category::Action),
+        (Status::VerifySender, Category::Action),
+        (Status::VerifySenderApp, Category::Action),
+        (Status::VerifyCaptcha, Category::Action),
+        (Status::VerifyMasterPass, Category::Action),
+        (Status::WaitAccept, Category::Pending),
+        (Status::WaitCard, Category::Action),
+        (Status::WaitCompensation, Category::Pending),
+        (Status::WaitLc, Category::Pending),
+        (Status::WaitReserve, Category::Pending),
+        (Status::WaitSecure, Category::Pending),
+        (Status::WaitQr, Category::Action),
+        (Status::WaitSender, Category::Action),
+        (Status::WaitCash, Category::Action),
+        (Status::WaitHold, Category::Pending),
+        (Status::WaitInvoice, Category::Action),
+        (Status::Subscribed, Category::Subscription),
+        (Status::Unsubscribed, Category::Final),
+        (Status::Prepared, Category::Action),
+        (Status::Processing, Category::Pending),
+        (Status::TryAgain, Category::Retryable),
+        (Status::Active, Category::Subscription),
+    ];
+
+    #[test]
+    fn every_status_has_expected_category() {
+        for (status, category) in STATUS_CATEGORIES {
+            let actual = if status.is_final() {
+                Category::Final
+            } else if status.is_retryable() {
+                Category::Retryable
+            } else if status.requires_action() {
+                Category::Action
+            } else if status.is_active_subscription() {
+                Category::Subscription
+            } else {
+                Category::Pending
+            };
+
+            assert_eq!(actual, category, "{}", status);
+        }
+    }
+
+    #[test]
+    fn every_status_is_in_exactly_one_category() {
+        for (status, _) in STATUS_CATEGORIES {
+            let categories = [
+                status.is_final(),
+                status.is_retryable(),
+                status.requires_action(),
+                status.is_active_subscription(),
+                status.is_pending(),
+            ];
+
+            assert_eq!(
+                categories.iter().filter(|&&category| category).count(),
+                1,
+                "{}",
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn monetary_value_serializes_to_amount_and_currency() {
+        let value = MonetaryValue::new(19.99, Currency::USD);
+
+        assert_eq!(
+            serde_json::to_value(value).unwrap(),
+            json!({ "amount": 19.99, "currency": "USD" })
+        );
+    }
+
+    #[test]
+    fn request_built_with_monetary_value_sends_both_fields() {
+        let request = CashPaymentRequest::with_value(
+            "public_key",
+            (19.99, Currency::USD),
+            "order".to_owned(),
+            "description".to_owned(),
+        );
+        let serialized_request = serde_json::to_value(request).unwrap();
+
+        assert_eq!(serialized_request["amount"], 19.99);
+        assert_eq!(serialized_request["currency"], "USD");
+    }
+
+    #[test]
+    fn amount_below_advisory_minimum_is_accepted() {
+        let request = CashPaymentRequest::with_value(
+            "public_key",
+            (0.5, Currency::UAH),
+            "order".to_owned(),
+            "description".to_owned(),
+        );
+
+        assert!(request.build().is_ok());
+    }
+
+    #[test]
+    fn amount_not_positive_once_rounded_is_rejected() {
+        for amount in [0.004, 0.0, -1.0] {
+            let request = CashPaymentRequest::with_value(
+                "public_key",
+                (amount, Currency::UAH),
+                "order".to_owned(),
+                "description".to_owned(),
+            );
+
+            assert!(request.build().is_err());
+        }
+    }
+
+    #[test]
+    fn order_id_over_limit_is_rejected() {
+        let longest_order_id = "a".repeat(ORDER_ID_MAX_LENGTH);
+        let too_long_order_id = "a".repeat(ORDER_ID_MAX_LENGTH + 1);
+
+        assert!(check_order_id(&longest_order_id).is_ok());
+        assert!(check_order_id(&too_long_order_id).is_err());
+        assert!(
+            StatusRequest::new("public_key", too_long_order_id)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn random_order_id_does_not_exceed_limit() {
+        let order_id = OrderId::random("order-");
+        let truncated_order_id = OrderId::random(&"p".repeat(ORDER_ID_MAX_LENGTH));
+
+        assert!(order_id.as_str().starts_with("order-"));
+        assert_ne!(order_id, OrderId::random("order-"));
+        assert_eq!(
+            truncated_order_id.as_str().chars().count(),
+            ORDER_ID_MAX_LENGTH
+        );
+    }
+
+    #[test]
+    fn prepare_serializes_to_liqpay_values() {
+        let cash_payment = || {
+            CashPaymentRequest::with_value(
+                "public_key",
+                (100.0, Currency::UAH),
+                "order".to_owned(),
+                "description".to_owned(),
+            )
+        };
+
+        let prepared = serde_json::to_value(cash_payment().prepare()).unwrap();
+        let tariffs = serde_json::to_value(cash_payment().prepare_tariffs()).unwrap();
+
+        assert_eq!(serde_json::to_value(Prepare::Enable).unwrap(), "1");
+        assert_eq!(serde_json::to_value(Prepare::Tariffs).unwrap(), "tariffs");
+        assert_eq!(prepared["prepare"], "1");
+        assert_eq!(tariffs["prepare"], "tariffs");
+    }
+
+    #[test]
+    fn amount_is_rounded_half_away_from_zero() {
+        assert_eq!(Amount::new(3.335).round(Currency::UAH), Amount::new(3.34));
+        assert_eq!(Amount::new(-3.335).round(Currency::UAH), Amount::new(-3.34));
+        assert_eq!(serde_json::to_string(&Amount::new(3.335)).unwrap(), "3.34");
+        assert_eq!(serde_json::to_string(&Amount::new(3.333)).unwrap(), "3.33");
+    }
+
+    #[test]
+    fn card_number_is_masked_except_last_digits() {
+        assert_eq!(mask_card_number("4242 4242 4242 4242"), "****4242");
+        assert_eq!(mask_card_number("12345"), "****2345");
+        assert_eq!(mask_card_number("1234"), "****");
+        assert_eq!(mask_card_number("12"), "****");
+        assert_eq!(mask_card_number(""), "****");
+    }
+
+    #[test]
+    fn canonical_key_wins_when_both_keys_are_present() {
+        let response: PaymentResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "sender_card_mask2": "424242*42",
+            "sender_card_mask": "4242",
+        }))
+        .unwrap();
+
+        assert_eq!(response.sender_card_mask.as_deref(), Some("424242*42"));
+        assert!(response.extra.is_empty());
+
+        let value = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(value["sender_card_mask2"], "424242*42");
+        assert!(value.get("sender_card_mask").is_none());
+    }
+
+    #[test]
+    fn alternate_key_is_used_when_canonical_key_is_absent() {
+        let response: StaticQrCodeResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "qr_code": "qr",
+        }))
+        .unwrap();
+
+        assert_eq!(response.qr_code.as_deref(), Some("qr"));
+    }
+
+    #[test]
+    fn result_is_deserialized_from_ok_success_and_error() {
+        assert_eq!(
+            serde_json::from_value::<Result>(json!("ok")).unwrap(),
+            Result::Ok
+        );
+        assert_eq!(
+            serde_json::from_value::<Result>(json!("success")).unwrap(),
+            Result::Ok
+        );
+        assert_eq!(
+            serde_json::from_value::<Result>(json!("error")).unwrap(),
+            Result::Error
+        );
+        assert!(serde_json::from_value::<Result>(json!("failure")).is_err());
+    }
+
+    #[test]
+    fn card_brand_is_detected_by_bin() {
+        assert_eq!(
+            CardBrand::detect("4242 4242 4242 4242"),
+            Some(CardBrand::Visa)
+        );
+        assert_eq!(
+            CardBrand::detect("5555555555554444"),
+            Some(CardBrand::MasterCard)
+        );
+        assert_eq!(
+            CardBrand::detect("2221000000000009"),
+            Some(CardBrand::MasterCard)
+        );
+        assert_eq!(
+            CardBrand::detect("9804000000000000"),
+            Some(CardBrand::Prostir)
+        );
+        assert_eq!(CardBrand::detect("1234567890123456"), None);
+        assert_eq!(CardBrand::detect("4242-abcd"), None);
+    }
+
+    #[test]
+    fn amount_round_trips_through_minor_units() {
+        for units in [0, 1, 99, 12_345, 100_000_000] {
+            for currency in [Currency::UAH, Currency::USD, Currency::EUR] {
+                assert_eq!(
+                    Amount::from_minor(units, currency).to_minor(currency),
+                    units
+                );
+            }
+        }
+
+        assert_eq!(Amount::from_minor(12_345, Currency::UAH).value(), 123.45);
+        assert_eq!(Amount::from(0.29).to_minor(Currency::UAH), 29);
+    }
+
+    #[test]
+    fn amount_is_formatted_for_locale() {
+        let amount = Amount::from(1_234_567.5);
+
+        assert_eq!(amount.format(Currency::UAH, Language::Uk), "1 234 567,50 ₴");
+        assert_eq!(amount.format(Currency::USD, Language::En), "$1,234,567.50");
+        assert_eq!(
+            Amount::from(0.05).format(Currency::UAH, Language::En),
+            "₴0.05"
+        );
+        assert_eq!(
+            Amount::from(-12.3).format(Currency::USD, Language::Uk),
+            "-12,30 $"
+        );
+    }
+
+    #[test]
+    fn iban_is_validated_by_checksum_and_length() {
+        assert!(validate_iban("UA213223130000026007233566001"));
+        assert!(validate_iban("ua21 3223 1300 0002 6007 2335 6600 1"));
+        assert!(validate_iban("GB82WEST12345698765432"));
+        assert!(!validate_iban("UA213223130000026007233566002"));
+        assert!(!validate_iban("UA2132231300"));
+        assert!(!validate_iban("UA2132231300000260072335660010000000"));
+        assert!(!validate_iban("21UA3223130000026007233566001"));
+    }
+
+    fn assert_wire_names<T: Serialize + Display>(variants: &[T], as_str: fn(&T) -> &'static str) {
+        for variant in variants {
+            let name = serde_json::to_value(variant).unwrap();
+
+            assert_eq!(as_str(variant), name);
+            assert_eq!(variant.to_string(), name);
+        }
+    }
+
+    #[test]
+    fn as_str_matches_serde_names() {
+        let actions = [
+            Action::Pay,
+            Action::SendInvoice,
+            Action::CancelInvoice,
+            Action::PayQrCode,
+            Action::CreateQrCode,
+            Action::PayToken,
+            Action::PayCash,
+            Action::PayTrack,
+            Action::Refund,
+            Action::Hold,
+            Action::HoldCompletion,
+            Action::Subscribe,
+            Action::UpdateSubscription,
+            Action::PayDonate,
+            Action::Auth,
+            Action::Status,
+            Action::Unsubscribe,
+            Action::Ticket,
+            Action::PaySplit,
+            Action::Regular,
+            Action::PreparePayment,
+            Action::P2PCredit,
+            Action::P2PDebit,
+            Action::P2P,
+            Action::CardVerification,
+            Action::Reports,
+            Action::CreateToken,
+            Action::CreateUniqueToken,
+            Action::UpdateToken,
+            Action::ReportsCompensation,
+            Action::ReportsCompensationFile,
+            Action::ReportsCompensationFileStatus,
+            Action::Registry,
+            Action::Data,
+            Action::CreateShop,
+            Action::RegisterShop,
+            Action::EditShop,
+            Action::MccCodes,
+            Action::MerchantInfo,
+            Action::UserInfo,
+            Action::GetInvoiceUnits,
+            Action::GetInvoiceUnitsByLanguage,
+            Action::Confirm,
+            Action::Mpi,
+        ];
+        let pay_types = [
+            PayType::Card,
+            PayType::LiqPay,
+            PayType::Privat24,
+            PayType::Masterpass,
+            PayType::MomentPart,
+            PayType::PayPart,
+            PayType::Cash,
+            PayType::Invoice,
+            PayType::QR,
+            PayType::ApplePay,
+            PayType::GooglePay,
+            PayType::ApplePayDecrypted,
+            PayType::GooglePayDecrypted,
+            PayType::Tavv,
+        ];
+        let currencies = [Currency::UAH, Currency::EUR, Currency::USD];
+        let statuses = STATUS_CATEGORIES.map(|(status, _)| status);
+
+        assert_wire_names(&actions, Action::as_str);
+        assert_wire_names(&pay_types, PayType::as_str);
+        assert_wire_names(&currencies, Currency::as_str);
+        assert_wire_names(&statuses, Status::as_str);
+        assert_eq!(Action::Pay.as_str(), "pay");
+        assert_eq!(Action::HoldCompletion.as_str(), "hold_completion");
+        assert_eq!(Action::UpdateToken.as_str(), "token_update");
+    }
+
+    /// Holds every field of the payment responses as they were named before the shared fields
+    /// were extracted into `PaymentResponse`.
+    fn payment_response_fields() -> Value {
+        serde_json::from_str(
+            r#"{
+                "result": "ok",
+                "status": "success",
+                "acq_id": 414963,
+                "action": "pay",
+                "agent_commission": 0.0,
+                "amount": 100.0,
+                "amount_bonus": 0.0,
+                "amount_credit": 100.0,
+                "amount_debit": 100.0,
+                "authcode_credit": "123456",
+                "authcode_debit": "654321",
+                "bonus_procent": 0.0,
+                "bonus_type": "bonusplus",
+                "card_token": "card_token",
+                "commission_credit": 0.0,
+                "commission_debit": 2.75,
+                "create_date": 1700000000000,
+                "currency": "UAH",
+                "currency_credit": "UAH",
+                "currency_debit": "UAH",
+                "description": "description",
+                "end_date": 1700000000000,
+                "ip": "127.0.0.1",
+                "is_3ds": false,
+                "liqpay_order_id": "liqpay_order",
+                "mpi_eci": "7",
+                "mpi_cres": "cres",
+                "order_id": "order",
+                "payment_id": 1,
+                "paytype": "card",
+                "public_key": "public_key",
+                "receiver_commission": 0.0,
+                "rrn_credit": "000000000001",
+                "rrn_debit": "000000000002",
+                "sender_bonus": 0.0,
+                "sender_card_bank": "Bank",
+                "sender_card_country": 804,
+                "sender_card_mask2": "424242*42",
+                "sender_card_type": "visa",
+                "sender_commission": 0.0,
+                "sender_first_name": "Taras",
+                "sender_last_name": "Shevchenko",
+                "sender_phone": "380950000001",
+                "transaction_id": 1,
+                "type": "buy",
+                "version": 3,
+                "language": "uk",
+                "confirm_phone": "380950000001",
+                "err_code": "err_code",
+                "err_description": "err_description"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn payment_of<T: DeserializeOwned>(payment: fn(T) -> PaymentResponse) -> PaymentResponse {
+        payment(serde_json::from_value(payment_response_fields()).unwrap())
+    }
+
+    #[test]
+    fn flattened_payment_responses_keep_every_field_name() {
+        let payments = [
+            payment_of(|response: CardPaymentResponse| response.payment),
+            payment_of(|response: FundsBlockingResponse| response.payment),
+            payment_of(|response: SubscribeResponse| response.payment),
+            payment_of(|response: TokenPaymentResponse| response.payment),
+            payment_of(|response: OtpResponse| response.payment),
+        ];
+
+        for payment in payments {
+            assert!(
+                payment.extra.is_empty(),
+                "unmodeled fields: {:?}",
+                payment.extra
+            );
+            assert_eq!(payment.acquirer_id, Some(414963));
+            assert_eq!(payment.sender_card_mask.as_deref(), Some("424242*42"));
+            assert_eq!(
+                payment.error_description.as_deref(),
+                Some("err_description")
+            );
+        }
+    }
+
+    #[test]
+    fn currency_is_parsed_from_its_code() {
+        assert_eq!("UAH".parse::<Currency>().unwrap(), Currency::UAH);
+        assert_eq!(Currency::try_from("USD").unwrap(), Currency::USD);
+        assert_eq!("EUR".parse::<Currency>().unwrap().to_string(), "EUR");
+        assert!("ZZZ".parse::<Currency>().is_err());
+        assert!(Currency::try_from("uah").is_err());
+    }
+
+    #[test]
+    fn responses_round_trip_through_serialization() {
+        let card_payment: CardPaymentResponse =
+            serde_json::from_value(payment_response_fields()).unwrap();
+        let static_qr_code: StaticQrCodeResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "qr_code": "qr_code",
+        }))
+        .unwrap();
+
+        let card_payment_copy: CardPaymentResponse =
+            serde_json::from_value(serde_json::to_value(&card_payment).unwrap()).unwrap();
+        let static_qr_code_copy: StaticQrCodeResponse =
+            serde_json::from_value(serde_json::to_value(&static_qr_code).unwrap()).unwrap();
+
+        assert_eq!(card_payment_copy, card_payment.clone());
+        assert_eq!(static_qr_code_copy, static_qr_code);
+    }
+
+    #[test]
+    fn pay_type_is_classified_for_each_variant() {
+        // (pay type, is_card, is_wallet, is_cash, is_qr)
+        let classification = [
+            (PayType::Card, true, false, false, false),
+            (PayType::Tavv, true, false, false, false),
+            (PayType::LiqPay, false, true, false, false),
+            (PayType::Privat24, false, true, false, false),
+            (PayType::Masterpass, false, true, false, false),
+            (PayType::ApplePay, false, true, false, false),
+            (PayType::GooglePay, false, true, false, false),
+            (PayType::ApplePayDecrypted, false, true, false, false),
+            (PayType::GooglePayDecrypted, false, true, false, false),
+            (PayType::Cash, false, false, true, false),
+            (PayType::QR, false, false, false, true),
+            (PayType::MomentPart, false, false, false, false),
+            (PayType::PayPart, false, false, false, false),
+            (PayType::Invoice, false, false, false, false),
+        ];
+
+        for (pay_type, card, wallet, cash, qr) in classification {
+            assert_eq!(
+                (
+                    pay_type.is_card(),
+                    pay_type.is_wallet(),
+                    pay_type.is_cash(),
+                    pay_type.is_qr()
+                ),
+                (card, wallet, cash, qr),
+                "{pay_type}"
+            );
+        }
+    }
+
+    #[test]
+    fn version_round_trips_through_its_number() {
+        assert_eq!(Version::Seven.as_u8(), 7);
+        assert_eq!(Version::Three.as_u8(), 3);
+
+        for version in [Version::Three, Version::Seven] {
+            assert_eq!(Version::try_from(version.as_u8()).unwrap(), version);
+            assert_eq!(
+                Version::try_from(version.as_u8().to_string().as_str()).unwrap(),
+                version
+            );
+        }
+
+        assert!(Version::try_from(5).is_err());
+        assert!(Version::try_from("seven").is_err());
+    }
+
+    fn split_payment(split_tickets: Value) -> PaymentResponse {
+        serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "split_tickets": split_tickets,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn simulated_split_is_deserialized_from_array_or_string() {
+        let tickets = json!([
+            { "public_key": "receiver_1", "amount": 70, "commission": "1.05", "commission_payer": "receiver" },
+            { "public_key": "receiver_2", "amount": 30.0, "commission": 0.45 },
+        ]);
+        let expected = vec![
+            SplitTicket {
+                public_key: Some("receiver_1".to_owned()),
+                amount: Some(70.0),
+                commission: Some(1.05),
+                commission_payer: Some("receiver".to_owned()),
+                extra: Default::default(),
+            },
+            SplitTicket {
+                public_key: Some("receiver_2".to_owned()),
+                amount: Some(30.0),
+                commission: Some(0.45),
+                commission_payer: None,
+                extra: Default::default(),
+            },
+        ];
+
+        for payment in [
+            split_payment(tickets.clone()),
+            split_payment(Value::String(tickets.to_string())),
+        ] {
+            assert!(payment.was_simulated());
+            assert_eq!(payment.simulated_split(), Some(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn payment_without_split_tickets_was_not_simulated() {
+        let payment: PaymentResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "success" })).unwrap();
+
+        assert!(!payment.was_simulated());
+        assert_eq!(payment.simulated_split(), None);
+    }
+
+    fn failed_payment(description: &str) -> PaymentResponse {
+        serde_json::from_value(json!({
+            "result": "error",
+            "status": "error",
+            "err_code": "err_auth",
+            "err_description": description,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn localized_error_description_is_returned_as_is() {
+        let payment = failed_payment("Потрібна авторизація");
+
+        assert_eq!(
+            payment.error_localized().as_deref(),
+            Some("Потрібна авторизація")
+        );
+    }
+
+    #[test]
+    fn empty_error_description_falls_back_to_error_code_meaning() {
+        for description in ["", "  "] {
+            assert_eq!(
+                failed_payment(description).error_localized().as_deref(),
+                Some("Authorization is required")
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// Represents an error, which occurs while building or sending a request to LiqPay.
+#[derive(Debug)]
+pub enum LiqPayError {
+    /// Represents a request, which has not passed the validation before being sent.
+    Validation(String),
+    /// Represents a failure to send a request or to receive a response.
+    Http(reqwest::Error),
+    /// Represents a failure to serialize a request or to deserialize a response.
+    Serialization(serde_json::Error),
+    /// Represents a failure to decode base64-encoded data.
+    Decoding(base64::DecodeError),
+    /// Represents a request rejected by LiqPay with `429 Too Many Requests`.
+    /// `retry_after` holds the delay from the `Retry-After` header, if LiqPay has sent one.
+    RateLimited { retry_after: Option<Duration> },
+    /// Represents a response, which is not a LiqPay reply, e.g. `503 Service Unavailable`
+    /// with an HTML page during maintenance. `body_snippet` holds the beginning of the response body.
+    Unavailable { status: u16, body_snippet: String },
+    /// Represents an error reply of LiqPay, i.e. a response with `result` set to `error`.
+    /// `code` and `description` hold `err_code` and `err_description` of the response.
+    Api {
+        code: Option<String>,
+        description: Option<String>,
+    },
+}
+
+impl Display for LiqPayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LiqPayError::Validation(message) => write!(f, "Invalid request: {}", message),
+            LiqPayError::Http(error) => write!(f, "HTTP error: {}", error),
+            LiqPayError::Serialization(error) => write!(f, "Serialization error: {}", error),
+            LiqPayError::Decoding(error) => write!(f, "Decoding error: {}", error),
+            LiqPayError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "Too many requests, retry after {} seconds",
+                retry_after.as_secs()
+            ),
+            LiqPayError::RateLimited { retry_after: None } => f.write_str("Too many requests"),
+            LiqPayError::Unavailable { status, .. } => {
+                write!(f, "LiqPay is unavailable, HTTP status {}", status)
+            }
+            LiqPayError::Api { code, description } => write!(
+                f,
+                "LiqPay error {}: {}",
+                code.as_deref().unwrap_or("unknown"),
+                description.as_deref().unwrap_or("no description")
+            ),
+        }
+    }
+}
+
+impl Error for LiqPayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LiqPayError::Http(error) => Some(error),
+            LiqPayError::Serialization(error) => Some(error),
+            LiqPayError::Decoding(error) => Some(error),
+            LiqPayError::Validation(_)
+            | LiqPayError::RateLimited { .. }
+            | LiqPayError::Unavailable { .. }
+            | LiqPayError::Api { .. } => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for LiqPayError {
+    fn from(error: reqwest::Error) -> Self {
+        LiqPayError::Http(error)
+    }
+}
+
+impl From<serde_json::Error> for LiqPayError {
+    fn from(error: serde_json::Error) -> Self {
+        LiqPayError::Serialization(error)
+    }
+}
+
+impl From<base64::DecodeError> for LiqPayError {
+    fn from(error: base64::DecodeError) -> Self {
+        LiqPayError::Decoding(error)
+    }
+}
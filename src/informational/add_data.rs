@@ -1,38 +1,56 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
 use crate::common::enums::{
     Action, Bonus, Currency, Language, MpiEci, PayType, Result, Status, Version,
 };
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::check_order_id;
+use crate::error::LiqPayError;
 
 /// Represents a request to add data to an existing payment.
 #[derive(Debug, Serialize)]
 pub struct AddDataRequest {
-    version: Version,
     public_key: String,
     action: Action,
     order_id: String,
     info: String,
 }
 
-impl LiqPayRequest<AddDataResponse, Sha3_256> for AddDataRequest {}
+impl LiqPayRequest<AddDataResponse, Sha3_256> for AddDataRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl AddDataRequest {
     /// Constructs a new request to add data to an existing payment.
     pub fn new(public_key: impl Into<String>, order_id: String, info: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Data,
             public_key: public_key.into(),
             order_id,
             info,
         }
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a data adding operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct AddDataResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -47,14 +65,19 @@ pub struct AddDataResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
@@ -68,8 +91,10 @@ pub struct AddDataResponse {
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the phone number used for confirmation via a one-time password.
     pub confirm_phone: Option<String>,
@@ -91,6 +116,7 @@ pub struct AddDataResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -114,7 +140,8 @@ pub struct AddDataResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the identifier of the transaction in a the issuer bank's system for credit.
     #[serde(rename = "rrn_credit")]
     pub retrieval_reference_number_credit: Option<String>,
@@ -122,17 +149,19 @@ pub struct AddDataResponse {
     #[serde(rename = "rrn_debit")]
     pub retrieval_reference_number_debit: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,
@@ -156,3 +185,13 @@ pub struct AddDataResponse {
 }
 
 impl LiqPayResponse for AddDataResponse {}
+
+impl AddDataResponse {
+    /// Parses the additional information about the payment as JSON.
+    /// Returns `None` if the information is absent or is not valid JSON.
+    pub fn info_json(&self) -> Option<Value> {
+        self.info
+            .as_deref()
+            .and_then(|info| serde_json::from_str(info).ok())
+    }
+}
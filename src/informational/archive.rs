@@ -1,14 +1,18 @@
+use std::result::Result as StdResult;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Status, Version};
+use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Result, Status, Version};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::error::LiqPayError;
 use crate::informational::common::ResponseFormat;
 
 /// Represents a request to get an archive of received payments.
 #[derive(Debug, Serialize)]
 pub struct ArchiveRequest {
-    version: Version,
     public_key: String,
     action: Action,
     date_from: String,
@@ -17,13 +21,16 @@ pub struct ArchiveRequest {
     response_format: ResponseFormat,
 }
 
-impl LiqPayRequest<ArchiveResponse, Sha3_256> for ArchiveRequest {}
+impl LiqPayRequest<ArchiveResponse, Sha3_256> for ArchiveRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl ArchiveRequest {
-    /// Constructs a new request to get an archive of received payments.
+    /// Constructs a new request to get an archive of received payments. The dates are timestamps in milliseconds.
     pub fn new(public_key: impl Into<String>, date_from: String, date_to: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Reports,
             public_key: public_key.into(),
             response_format: ResponseFormat::Json,
@@ -31,10 +38,91 @@ impl ArchiveRequest {
             date_to,
         }
     }
+
+    /// Constructs a new request to get an archive of received payments made between two dates.
+    /// The dates are formatted as timestamps in milliseconds. Returns an error if `date_from` is later
+    /// than `date_to`.
+    #[cfg(feature = "chrono")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+    pub fn from_date_range(
+        public_key: impl Into<String>,
+        date_from: DateTime<Utc>,
+        date_to: DateTime<Utc>,
+    ) -> StdResult<Self, LiqPayError> {
+        Self::new(
+            public_key,
+            date_from.timestamp_millis().to_string(),
+            date_to.timestamp_millis().to_string(),
+        )
+        .build()
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        let date_from = parse_timestamp("date_from", &self.date_from)?;
+        let date_to = parse_timestamp("date_to", &self.date_to)?;
+
+        if date_from > date_to {
+            return Err(LiqPayError::Validation(
+                "`date_from` must not be later than `date_to`".to_string(),
+            ));
+        }
+
+        Ok(self)
+    }
+}
+
+/// Represents the length of a day in milliseconds.
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Represents an iterator over requests to get an archive of received payments, each of which
+/// covers a single day of a date range.
+#[derive(Debug, Clone)]
+pub struct DailyArchiveRequests {
+    public_key: String,
+    next_from: Option<i64>,
+    date_to: i64,
+}
+
+impl DailyArchiveRequests {
+    /// Constructs a new iterator over a date range. The dates are timestamps in milliseconds,
+    /// and both of them are included in the range.
+    pub fn new(public_key: impl Into<String>, date_from: i64, date_to: i64) -> Self {
+        Self {
+            public_key: public_key.into(),
+            next_from: Some(date_from),
+            date_to,
+        }
+    }
+}
+
+impl Iterator for DailyArchiveRequests {
+    type Item = ArchiveRequest;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date_from = self
+            .next_from
+            .filter(|date_from| *date_from <= self.date_to)?;
+        let date_to = date_from.saturating_add(DAY_MILLIS - 1).min(self.date_to);
+
+        self.next_from = date_to.checked_add(1);
+
+        Some(ArchiveRequest::new(
+            self.public_key.clone(),
+            date_from.to_string(),
+            date_to.to_string(),
+        ))
+    }
+}
+
+fn parse_timestamp(field: &str, value: &str) -> StdResult<i64, LiqPayError> {
+    value.parse().map_err(|_| {
+        LiqPayError::Validation(format!("`{}` must be a timestamp in milliseconds", field))
+    })
 }
 
 /// Represents an archive payment entry.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ArchiveEntry {
     /// Represents the status of the request. Possible values are `error` - incorrect data,
     /// `failure` - payment failed, `reversed` - payment refunded, `success` - successful payment,
@@ -52,18 +140,25 @@ pub struct ArchiveEntry {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -79,6 +174,7 @@ pub struct ArchiveEntry {
     /// Represents the end date of the payment.
     pub end_date: Option<u64>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -100,19 +196,22 @@ pub struct ArchiveEntry {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the identifier of a transaction in LiqPay.
     pub transaction_id: Option<u64>,
@@ -129,19 +228,10 @@ pub struct ArchiveEntry {
     pub error_description: Option<String>,
 }
 
-/// Represents an operation result.
-#[derive(Debug, Deserialize)]
-pub enum Result {
-    #[serde(rename = "success")]
-    Success,
-    #[serde(rename = "error")]
-    Error,
-}
-
 /// Represents the response to getting an archive of received payments operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ArchiveResponse {
-    /// Represents the result of the request. Can be either `success` or `error`.
+    /// Represents the result of the request. Can be either `ok` or `error`, `success` is treated as `ok`.
     pub result: Result,
     /// Represents the status of the request.
     pub status: Option<Status>,
@@ -156,3 +246,62 @@ pub struct ArchiveResponse {
 }
 
 impl LiqPayResponse for ArchiveResponse {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    use chrono::DateTime;
+
+    use super::ArchiveRequest;
+    use crate::error::LiqPayError;
+
+    #[test]
+    fn reversed_date_range_is_rejected() {
+        let request = ArchiveRequest::new(
+            "public_key",
+            "1700086400000".to_owned(),
+            "1700000000000".to_owned(),
+        );
+
+        assert!(matches!(request.build(), Err(LiqPayError::Validation(_))));
+    }
+
+    #[test]
+    fn non_timestamp_date_is_rejected() {
+        let request = ArchiveRequest::new(
+            "public_key",
+            "2023-11-15".to_owned(),
+            "1700086400000".to_owned(),
+        );
+
+        assert!(matches!(request.build(), Err(LiqPayError::Validation(_))));
+    }
+
+    #[test]
+    fn valid_date_range_is_accepted() {
+        let request = ArchiveRequest::new(
+            "public_key",
+            "1700000000000".to_owned(),
+            "1700086400000".to_owned(),
+        );
+
+        assert!(request.build().is_ok());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date_range_is_formatted_and_validated() {
+        let date_from = DateTime::from_timestamp_millis(1_700_000_000_000).unwrap();
+        let date_to = DateTime::from_timestamp_millis(1_700_086_400_000).unwrap();
+
+        let request = ArchiveRequest::from_date_range("public_key", date_from, date_to).unwrap();
+        let data = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(data["date_from"], "1700000000000");
+        assert_eq!(data["date_to"], "1700086400000");
+        assert!(matches!(
+            ArchiveRequest::from_date_range("public_key", date_to, date_from),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::Serialize;
 
-/// Represents a report format in a response.
+/// Represents a report format in a response. Reports, which the crate parses, e.g. the archive
+/// and the registry, are always requested as JSON. The format can be chosen only for report files,
+/// which are downloaded as they are, such as compensation report files.
 #[derive(Debug, Serialize)]
 pub enum ResponseFormat {
     #[serde(rename = "json")]
@@ -1,13 +1,17 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Language, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::enums::{Action, Language, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::check_order_id;
+use crate::error::LiqPayError;
 
 /// Represents a request to send a receipt to an email address.
 #[derive(Debug, Serialize)]
 pub struct SendReceiptRequest {
-    version: Version,
     public_key: String,
     action: Action,
     email: String,
@@ -18,13 +22,26 @@ pub struct SendReceiptRequest {
     language: Option<Language>,
 }
 
-impl LiqPayRequest<SendReceiptResponse, Sha3_256> for SendReceiptRequest {}
+impl LiqPayRequest<SendReceiptResponse, Sha3_256> for SendReceiptRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl SendReceiptRequest {
     /// Constructs a new request to send a receipt to an email address.
     pub fn new(public_key: impl Into<String>, email: String, order_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Ticket,
             public_key: public_key.into(),
             email,
@@ -45,10 +62,17 @@ impl SendReceiptRequest {
         self.language = Some(language);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents a response to sending a receipt to an email address operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SendReceiptResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
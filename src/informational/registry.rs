@@ -1,14 +1,13 @@
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Bonus, Currency, PayType, Result, Status, Version};
+use crate::common::enums::{Action, Bonus, Currency, PayType, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 use crate::informational::common::ResponseFormat;
 
 /// Represents a request to get a compensation report.
 #[derive(Debug, Serialize)]
 pub struct CompensationReportRequest {
-    version: Version,
     public_key: String,
     action: Action,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -19,13 +18,16 @@ pub struct CompensationReportRequest {
     response_format: ResponseFormat,
 }
 
-impl LiqPayRequest<CompensationReportResponse, Sha3_256> for CompensationReportRequest {}
+impl LiqPayRequest<CompensationReportResponse, Sha3_256> for CompensationReportRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl CompensationReportRequest {
     /// Constructs a new request to get a compensation report by a compensation identifier.
     pub fn by_compensation_id(public_key: impl Into<String>, compensation_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensation,
             public_key: public_key.into(),
             compensation_id: Some(compensation_id),
@@ -37,7 +39,6 @@ impl CompensationReportRequest {
     /// Constructs a new request to get a compensation report by a specific date.
     pub fn by_date(public_key: impl Into<String>, date: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensation,
             public_key: public_key.into(),
             date: Some(date),
@@ -48,7 +49,7 @@ impl CompensationReportRequest {
 }
 
 /// Represents a payment channel.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Channel {
     #[serde(rename = "checkout")]
     Checkout,
@@ -60,7 +61,7 @@ pub enum Channel {
 
 // maybe some of the fields can be made just types without Option<>
 /// Represents an entry in a registry report.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RegistryReportEntry {
     /// Represents an identifier of a registry report entry.
     pub id: u32,
@@ -73,19 +74,39 @@ pub struct RegistryReportEntry {
     #[serde(rename = "trans_type")]
     pub transaction_type: Option<String>,
     /// Represents the transaction amount.
-    #[serde(rename = "trans_amount")]
+    #[serde(
+        rename = "trans_amount",
+        default,
+        deserialize_with = "crate::common::de::optional_f64"
+    )]
     pub transaction_amount: Option<f64>,
     /// Represents the transaction fee for debit.
-    #[serde(rename = "trans_fee_debit")]
+    #[serde(
+        rename = "trans_fee_debit",
+        default,
+        deserialize_with = "crate::common::de::optional_f64"
+    )]
     pub transaction_fee_debit: Option<f64>,
     /// Represents the transaction fee for credit.
-    #[serde(rename = "trans_fee_credit")]
+    #[serde(
+        rename = "trans_fee_credit",
+        default,
+        deserialize_with = "crate::common::de::optional_f64"
+    )]
     pub transaction_fee_credit: Option<f64>,
     /// Represents the transaction bonus.
-    #[serde(rename = "trans_bonus")]
+    #[serde(
+        rename = "trans_bonus",
+        default,
+        deserialize_with = "crate::common::de::optional_f64"
+    )]
     pub transaction_bonus: Option<f64>,
     /// Represents the transaction total amount.
-    #[serde(rename = "trans_total")]
+    #[serde(
+        rename = "trans_total",
+        default,
+        deserialize_with = "crate::common::de::optional_f64"
+    )]
     pub transaction_total: Option<f64>,
     /// Represents the transaction currency.
     #[serde(rename = "trans_currency")]
@@ -117,6 +138,7 @@ pub struct RegistryReportEntry {
     /// Represents the bonus type. Possible values are `bonusplus`, `personal`, `promo` and `discount_club`.
     pub bonus_type: Option<Bonus>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's card.
     pub sender_card: Option<String>,
@@ -145,7 +167,7 @@ pub struct RegistryReportEntry {
 }
 
 /// Represents the response to a compensation report operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CompensationReportResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -166,20 +188,22 @@ impl LiqPayResponse for CompensationReportResponse {}
 /// Represents a request to get a registry report.
 #[derive(Debug, Serialize)]
 pub struct RegistryRequest {
-    version: Version,
     action: Action,
     public_key: String,
     format: ResponseFormat,
     date: String,
 }
 
-impl LiqPayRequest<RegistryResponse, Sha3_256> for RegistryRequest {}
+impl LiqPayRequest<RegistryResponse, Sha3_256> for RegistryRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl RegistryRequest {
     /// Constructs a new request to get a registry report.
     pub fn new(public_key: impl Into<String>, date: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Registry,
             public_key: public_key.into(),
             format: ResponseFormat::Json,
@@ -189,7 +213,7 @@ impl RegistryRequest {
 }
 
 /// Represents the response to a registry report operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RegistryResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -210,7 +234,6 @@ impl LiqPayResponse for RegistryResponse {}
 /// Represents a request to get a compensation report file.
 #[derive(Debug, Serialize)]
 pub struct CompensationReportFileRequest {
-    version: Version,
     action: Action,
     public_key: String,
     response_format: ResponseFormat,
@@ -220,13 +243,16 @@ pub struct CompensationReportFileRequest {
     date: Option<String>,
 }
 
-impl LiqPayRequest<CompensationReportFileResponse, Sha3_256> for CompensationReportFileRequest {}
+impl LiqPayRequest<CompensationReportFileResponse, Sha3_256> for CompensationReportFileRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl CompensationReportFileRequest {
     /// Constructs a new request to get a compensation report file by a compensation identifier.
     pub fn by_date(public_key: impl Into<String>, date: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensationFile,
             public_key: public_key.into(),
             response_format: ResponseFormat::Csv,
@@ -238,7 +264,6 @@ impl CompensationReportFileRequest {
     /// Constructs a new request to get a compensation report file by a specific date.
     pub fn by_compensation_id(public_key: impl Into<String>, compensation_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensationFile,
             public_key: public_key.into(),
             response_format: ResponseFormat::Csv,
@@ -246,10 +271,16 @@ impl CompensationReportFileRequest {
             date: None,
         }
     }
+
+    /// Sets the format of the report file. Defaults to CSV.
+    pub fn format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
 }
 
 /// Represents a request to get a compensation report file.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CompensationReportFileResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -271,7 +302,6 @@ impl LiqPayResponse for CompensationReportFileResponse {}
 /// Represents a request to get a compensation report file status.
 #[derive(Debug, Serialize)]
 pub struct CompensationReportFileStatusRequest {
-    version: Version,
     action: Action,
     public_key: String,
     #[serde(rename = "register_token")]
@@ -281,13 +311,15 @@ pub struct CompensationReportFileStatusRequest {
 impl LiqPayRequest<CompensationReportFileStatusResponse, Sha3_256>
     for CompensationReportFileStatusRequest
 {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
 }
 
 impl CompensationReportFileStatusRequest {
     /// Constructs a new request to get a compensation report file status.
     pub fn new(public_key: impl Into<String>, registration_token: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensationFileStatus,
             public_key: public_key.into(),
             registration_token,
@@ -296,7 +328,7 @@ impl CompensationReportFileStatusRequest {
 }
 
 /// Represents a request to get a compensation report file status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CompensationReportFileStatusResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -327,7 +359,6 @@ pub enum P2PCompensationReportFileType {
 /// Represents a request to get a P2P compensation report file.
 #[derive(Debug, Serialize)]
 pub struct P2PCompensationReportFileRequest {
-    version: Version,
     action: Action,
     public_key: String,
     response_format: ResponseFormat,
@@ -336,7 +367,11 @@ pub struct P2PCompensationReportFileRequest {
     date: String,
 }
 
-impl LiqPayRequest<CompensationReportFileResponse, Sha3_256> for P2PCompensationReportFileRequest {}
+impl LiqPayRequest<CompensationReportFileResponse, Sha3_256> for P2PCompensationReportFileRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl P2PCompensationReportFileRequest {
     /// Constructs a new request to get a P2P compensation report file.
@@ -349,13 +384,18 @@ impl P2PCompensationReportFileRequest {
         Self::new(public_key, date, P2PCompensationReportFileType::P2PCredit)
     }
 
+    /// Sets the format of the report file. Defaults to CSV.
+    pub fn format(mut self, format: ResponseFormat) -> Self {
+        self.response_format = format;
+        self
+    }
+
     fn new(
         public_key: impl Into<String>,
         date: String,
         operation_type: P2PCompensationReportFileType,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::ReportsCompensationFile,
             public_key: public_key.into(),
             response_format: ResponseFormat::Csv,
@@ -364,3 +404,50 @@ impl P2PCompensationReportFileRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::Value;
+
+    use super::{
+        CompensationReportFileRequest, CompensationReportRequest, P2PCompensationReportFileRequest,
+        RegistryRequest,
+    };
+    use crate::informational::common::ResponseFormat;
+
+    fn serialize(request: impl Serialize) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn parsed_reports_are_requested_as_json() {
+        let date = || "2024-01-01".to_owned();
+
+        assert_eq!(
+            serialize(CompensationReportRequest::by_date("public_key", date()))["resp_format"],
+            "json"
+        );
+        assert_eq!(
+            serialize(RegistryRequest::new("public_key", date()))["format"],
+            "json"
+        );
+    }
+
+    #[test]
+    fn report_file_format_defaults_to_csv_and_can_be_changed() {
+        let date = || "2024-01-01".to_owned();
+        let default = serialize(CompensationReportFileRequest::by_date("public_key", date()));
+        let xml = serialize(
+            CompensationReportFileRequest::by_date("public_key", date())
+                .format(ResponseFormat::Xml),
+        );
+        let p2p_xml = serialize(
+            P2PCompensationReportFileRequest::p2p("public_key", date()).format(ResponseFormat::Xml),
+        );
+
+        assert_eq!(default["response_format"], "csv");
+        assert_eq!(xml["response_format"], "xml");
+        assert_eq!(p2p_xml["response_format"], "xml");
+    }
+}
@@ -1,36 +1,57 @@
+use std::collections::BTreeMap;
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
 use crate::common::enums::{
     Action, Bonus, Currency, Language, MpiEci, PayType, Result, Status, Version,
 };
+use crate::common::responses::localized_error;
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::check_order_id;
+use crate::error::LiqPayError;
 
-/// Represents a request to get a payment status.
+/// Represents a request to get a payment status. LiqPay has no action to list or query subscriptions,
+/// so the state of a subscription is requested by its `order_id` with this request.
 #[derive(Debug, Serialize)]
 pub struct StatusRequest {
-    version: Version,
     public_key: String,
     action: Action,
     order_id: String,
 }
 
-impl LiqPayRequest<StatusResponse, Sha3_256> for StatusRequest {}
+impl LiqPayRequest<StatusResponse, Sha3_256> for StatusRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl StatusRequest {
     /// Constructs a new request to get a payment status.
     pub fn new(public_key: impl Into<String>, order_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Status,
             public_key: public_key.into(),
             order_id,
         }
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to getting a payment status operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct StatusResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -62,14 +83,19 @@ pub struct StatusResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
@@ -83,8 +109,10 @@ pub struct StatusResponse {
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the phone number used for confirmation via a one-time password.
     pub confirm_phone: Option<String>,
@@ -106,6 +134,7 @@ pub struct StatusResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -129,7 +158,8 @@ pub struct StatusResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the identifier of the transaction in a the issuer bank's system for credit.
     #[serde(rename = "rrn_credit")]
     pub retrieval_reference_number_credit: Option<String>,
@@ -137,17 +167,19 @@ pub struct StatusResponse {
     #[serde(rename = "rrn_debit")]
     pub retrieval_reference_number_debit: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,
@@ -170,6 +202,216 @@ pub struct StatusResponse {
     /// Holds an error description.
     #[serde(rename = "err_description")]
     pub error_description: Option<String>,
+    /// Holds the fields, which are not modeled by the response.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl LiqPayResponse for StatusResponse {}
+
+impl StatusResponse {
+    /// Returns the description of the error, if any. LiqPay sends `err_description` in the `language`
+    /// of the request, so the description is localized when the request sets a language.
+    /// If the description is empty, the English meaning of `err_code` is returned instead.
+    pub fn error_localized(&self) -> Option<String> {
+        localized_error(
+            self.error_code.as_deref(),
+            self.error_description.as_deref(),
+        )
+    }
+
+    /// Parses the additional information about the payment as JSON.
+    /// Returns `None` if the information is absent or is not valid JSON.
+    pub fn info_json(&self) -> Option<Value> {
+        self.info
+            .as_deref()
+            .and_then(|info| serde_json::from_str(info).ok())
+    }
+
+    /// Indicates whether the payment has been softly declined, e.g. with `try_again`, and may be retried.
+    /// Unlike `failure`, a soft decline is not final for the order: the payment is retried
+    /// as a new attempt with the same `order_id`.
+    pub fn is_retryable(&self) -> bool {
+        self.status.is_retryable()
+    }
+
+    /// Returns the subscription-specific data, if the payment is a subscription.
+    pub fn subscription_status(&self) -> Option<SubscriptionStatus> {
+        let is_subscription = matches!(self.action, Some(Action::Subscribe))
+            || matches!(self.status, Status::Subscribed | Status::Unsubscribed);
+
+        if !is_subscription {
+            return None;
+        }
+
+        Some(SubscriptionStatus {
+            is_active: matches!(self.status, Status::Subscribed),
+            order_id: self.order_id.clone(),
+            amount: self.amount,
+            currency: self.currency.clone(),
+            description: self.description.clone(),
+            card_token: self.card_token.clone(),
+        })
+    }
+}
+
+/// Represents the state of a subscription taken from a payment status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionStatus {
+    /// Indicates whether the subscription is active.
+    pub is_active: bool,
+    /// Represents the identifier of the subscription's order.
+    pub order_id: Option<String>,
+    /// Represents the amount of a regular payment.
+    pub amount: Option<f64>,
+    /// Represents the currency of a regular payment.
+    pub currency: Option<String>,
+    /// Represents the subscription description.
+    pub description: Option<String>,
+    /// Represents the card token used for regular payments.
+    pub card_token: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{StatusRequest, StatusResponse};
+    use crate::common::enums::Currency;
+    use crate::internet_acquiring::card::CardPaymentRequest;
+
+    #[test]
+    fn unknown_response_fields_are_kept_in_extra() {
+        let response: StatusResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "order_id": "order",
+            "new_field": "value",
+        }))
+        .unwrap();
+
+        assert_eq!(response.extra["new_field"], "value");
+        assert_eq!(response.extra.len(), 1);
+    }
+
+    fn response_with(field: &str, value: serde_json::Value) -> StatusResponse {
+        let mut response = json!({ "result": "ok", "status": "success" });
+        response[field] = value;
+
+        serde_json::from_value(response).unwrap()
+    }
+
+    #[test]
+    fn is_3ds_is_accepted_as_boolean_or_string() {
+        assert_eq!(response_with("is_3ds", json!(true)).is_3ds, Some(true));
+        assert_eq!(response_with("is_3ds", json!("true")).is_3ds, Some(true));
+        assert_eq!(response_with("is_3ds", json!(false)).is_3ds, Some(false));
+        assert_eq!(response_with("is_3ds", json!("false")).is_3ds, Some(false));
+        assert_eq!(response_with("is_3ds", json!(null)).is_3ds, None);
+    }
+
+    #[test]
+    fn amounts_are_accepted_as_numbers_or_numeric_strings() {
+        assert_eq!(response_with("amount", json!(10.5)).amount, Some(10.5));
+        assert_eq!(response_with("amount", json!("10.50")).amount, Some(10.5));
+        assert_eq!(
+            response_with("amount_debit", json!("10.50")).amount_debit,
+            Some(10.5)
+        );
+
+        let response = json!({ "result": "ok", "status": "success", "amount": "ten" });
+        assert!(serde_json::from_value::<StatusResponse>(response).is_err());
+    }
+
+    #[test]
+    fn receiver_commission_is_accepted_as_integer_float_or_string() {
+        let commission = |value| response_with("receiver_commission", value).receiver_commission;
+
+        assert_eq!(commission(json!(3)), Some(3.0));
+        assert_eq!(commission(json!(2.75)), Some(2.75));
+        assert_eq!(commission(json!("2.75")), Some(2.75));
+        assert_eq!(
+            commission(json!(12345678.91)),
+            Some(12345678.91),
+            "precision is kept for large commissions"
+        );
+    }
+
+    #[test]
+    fn subscription_is_queried_with_status_request() {
+        let request = StatusRequest::new("public_key", "subscription".to_owned());
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            json!({ "action": "status", "public_key": "public_key", "order_id": "subscription" })
+        );
+    }
+
+    #[test]
+    fn subscription_status_is_returned_for_subscriptions_only() {
+        let subscribed: StatusResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "subscribed",
+            "action": "subscribe",
+            "order_id": "subscription",
+            "amount": 100.0,
+            "currency": "UAH",
+            "card_token": "card_token",
+        }))
+        .unwrap();
+        let unsubscribed: StatusResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "unsubscribed" })).unwrap();
+        let payment = response_with("action", json!("pay"));
+
+        let status = subscribed.subscription_status().unwrap();
+
+        assert!(status.is_active);
+        assert_eq!(status.order_id.as_deref(), Some("subscription"));
+        assert_eq!(status.amount, Some(100.0));
+        assert_eq!(status.card_token.as_deref(), Some("card_token"));
+        assert!(!unsubscribed.subscription_status().unwrap().is_active);
+        assert!(payment.subscription_status().is_none());
+    }
+
+    #[test]
+    fn info_json_sent_with_payment_is_read_back_from_status() {
+        let info = json!({ "cart_id": 42, "tags": ["gift"] });
+        let request = CardPaymentRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+        .info_json(info.clone());
+        let sent_info = serde_json::to_value(request).unwrap()["info"].clone();
+
+        assert!(sent_info.is_string());
+        assert_eq!(response_with("info", sent_info).info_json(), Some(info));
+        assert_eq!(response_with("info", json!("plain text")).info_json(), None);
+        assert_eq!(response_with("result", json!("ok")).info_json(), None);
+    }
+
+    #[test]
+    fn error_localized_falls_back_to_error_code_meaning() {
+        let localized = response_with("err_description", json!("Потрібна авторизація"));
+        let unknown_code = response_with("err_code", json!("custom_error"));
+
+        assert_eq!(
+            localized.error_localized().as_deref(),
+            Some("Потрібна авторизація")
+        );
+        assert_eq!(
+            response_with("err_code", json!("err_auth"))
+                .error_localized()
+                .as_deref(),
+            Some("Authorization is required")
+        );
+        assert_eq!(
+            unknown_code.error_localized().as_deref(),
+            Some("custom_error")
+        );
+    }
+}
@@ -1,28 +1,38 @@
+use std::result::Result as StdResult;
+
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
-use crate::common::enums::{
-    Action, Bonus, Currency, Language, MpiEci, PayType, Prepare, Result, Status, Version,
-};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Prepare};
+use crate::common::responses::PaymentResponse;
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_email, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::{DetailAddenda, ElectronicCommerceIndicator, RroInfo};
+use crate::internet_acquiring::two_step::FundsBlockingRequest;
 
 /// Represents a request to perform a card payment.
 #[derive(Debug, Serialize)]
 pub struct CardPaymentRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
-    card: String,
+    amount: Amount,
+    card: CardNumber,
     card_exp_month: String,
     card_exp_year: String,
     currency: Currency,
     order_id: String,
     description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ip: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,6 +54,8 @@ pub struct CardPaymentRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     recurring: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    is_recurring: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(rename = "eci", skip_serializing_if = "Option::is_none")]
     electronic_commerce_indicator: Option<ElectronicCommerceIndicator>,
@@ -54,6 +66,8 @@ pub struct CardPaymentRequest {
     #[serde(rename = "dsTransID", skip_serializing_if = "Option::is_none")]
     three_ds_transaction_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    mpi_eci: Option<MpiEci>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     rro_info: Option<RroInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     split_rules: Option<String>,
@@ -79,8 +93,12 @@ pub struct CardPaymentRequest {
     sender_postal_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
-    #[serde(rename = "dae", skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
     info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -93,29 +111,44 @@ pub struct CardPaymentRequest {
     product_url: Option<String>,
 }
 
-impl LiqPayRequest<CardPaymentResponse, Sha3_256> for CardPaymentRequest {}
+impl LiqPayRequest<CardPaymentResponse, Sha3_256> for CardPaymentRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl CardPaymentRequest {
     /// Constructs a new card payment request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         card: String,
         exp_month: String,
         exp_year: String,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::Pay,
             public_key: public_key.into(),
-            amount,
-            card,
+            amount: value.amount(),
+            card: card.into(),
             card_exp_month: exp_month,
             card_exp_year: exp_year,
-            currency,
+            currency: value.currency(),
             order_id,
             description,
             card_cvv: None,
@@ -129,11 +162,13 @@ impl CardPaymentRequest {
             recurring_by_token: None,
             result_url: None,
             recurring: None,
+            is_recurring: None,
             server_url: None,
             electronic_commerce_indicator: None,
             cardholder_authentication_verification_value: None,
             three_ds_version: None,
             three_ds_transaction_id: None,
+            mpi_eci: None,
             rro_info: None,
             split_rules: None,
             split_tickets_only: None,
@@ -156,9 +191,32 @@ impl CardPaymentRequest {
         }
     }
 
+    /// Constructs a new card payment request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        card: String,
+        exp_month: String,
+        exp_year: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            exp_month,
+            exp_year,
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the card CVV/CVV2 code.
     pub fn cvv(mut self, cvv: String) -> Self {
-        self.card_cvv = Some(cvv);
+        self.card_cvv = Some(cvv.into());
         self
     }
 
@@ -238,6 +296,19 @@ impl CardPaymentRequest {
         self
     }
 
+    /// Sets the payment as initiated by the merchant (MIT), i.e. performed without a client.
+    /// Unlike a subscription, LiqPay does not schedule further payments.
+    pub fn merchant_initiated(mut self) -> Self {
+        self.is_recurring = Some(true);
+        self
+    }
+
+    /// Sets the payment as initiated by the customer (CIT), i.e. performed with a client.
+    pub fn customer_initiated(mut self) -> Self {
+        self.is_recurring = Some(false);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -270,6 +341,12 @@ impl CardPaymentRequest {
         self
     }
 
+    /// Sets the MPI ECI code of a 3DS verification performed on the merchant's side.
+    pub fn mpi_eci(mut self, eci: MpiEci) -> Self {
+        self.mpi_eci = Some(eci);
+        self
+    }
+
     /// Sets a fiscalization data.
     pub fn rro_info(mut self, info: RroInfo) -> Self {
         self.rro_info = Some(info);
@@ -308,7 +385,7 @@ impl CardPaymentRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -352,7 +429,7 @@ impl CardPaymentRequest {
 
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -362,6 +439,12 @@ impl CardPaymentRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
     /// Sets the product category. Must not exceed 25 symbols.
     pub fn product_category(mut self, category: String) -> Self {
         self.product_category = Some(category);
@@ -392,133 +475,269 @@ impl CardPaymentRequest {
 
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
+
+    /// Converts the request to a request to block funds on the same card, so that the payment
+    /// is captured later. The fields, which a funds blocking request does not support, are dropped.
+    pub fn into_hold(self) -> FundsBlockingRequest {
+        FundsBlockingRequest {
+            public_key: self.public_key,
+            action: Action::Hold,
+            amount: self.amount,
+            currency: self.currency,
+            order_id: self.order_id,
+            description: self.description,
+            apple_pay_token: None,
+            card: Some(self.card),
+            card_cvv: self.card_cvv,
+            card_exp_month: Some(self.card_exp_month),
+            card_exp_year: Some(self.card_exp_year),
+            google_pay_token: None,
+            ip: self.ip,
+            phone: self.phone,
+            pay_type: self.pay_type,
+            tid: self.tid,
+            language: self.language,
+            prepare: self.prepare,
+            recurring_by_token: self.recurring_by_token,
+            recurring: self.recurring,
+            is_recurring: self.is_recurring,
+            server_url: self.server_url,
+            tavv: self.tavv,
+            electronic_commerce_indicator: self.electronic_commerce_indicator,
+            cardholder_authentication_verification_value: self
+                .cardholder_authentication_verification_value,
+            three_ds_version: self.three_ds_version,
+            three_ds_transaction_id: self.three_ds_transaction_id,
+            mpi_eci: self.mpi_eci,
+            sender_first_name: self.sender_first_name,
+            sender_last_name: self.sender_last_name,
+            sender_email: self.sender_email,
+            sender_country_code: self.sender_country_code,
+            sender_city: self.sender_city,
+            sender_address: self.sender_address,
+            sender_state: self.sender_state,
+            sender_shipping_state: self.sender_shipping_state,
+            sender_postal_code: self.sender_postal_code,
+            split_rules: self.split_rules,
+            customer: self.customer,
+            detail_addenda: self.detail_addenda,
+            info: self.info,
+        }
+    }
 }
 
 /// Represents the response to a card payment operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CardPaymentResponse {
-    /// Represents the result of the request. Can be either `ok` or `error`.
-    pub result: Result,
-    /// Represents the status of the request. Possible values are `error` - incorrect data,
-    /// `failure` - payment failed, `reversed` - payment refunded, `success` - successful payment,
-    /// `3ds_verify` - the verification by 3DS is required, `cvv_verify` - CVV required,
-    /// `otp_verify` - confirmation by OTP is required, `receiver_verify` - additional receiver
-    /// information is required, `sender_verify` - additional sender information is required,
-    /// `wait_accept` - the store is not verified at this point, but the money are withdrawn from a client,
-    /// `wait_secure` - verified payment.
-    pub status: Status,
-    /// Represents the identifier of an acquirer.
-    #[serde(rename = "acq_id")]
-    pub acquirer_id: Option<u32>,
-    /// Represents the operation type. Possible values are `pay` - payment,
-    /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation,
-    /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
-    pub action: Option<Action>,
-    /// Represents the agent commission.
-    pub agent_commission: Option<f64>,
-    /// Represents the payment amount.
-    pub amount: Option<f64>,
-    /// Represents the payer bonus amount.
-    pub amount_bonus: Option<f64>,
-    /// Represents the payment credit amount.
-    pub amount_credit: Option<f64>,
-    /// Represents the payment debit amount.
-    pub amount_debit: Option<f64>,
-    /// Represents the authorization code for credit.
-    pub authcode_credit: Option<String>,
-    /// Represents the authorization code for debit.
-    pub authcode_debit: Option<String>,
-    /// Represents the bonus percentage.
-    #[serde(rename = "bonus_procent")]
-    pub bonus_percent: Option<f32>,
-    /// Represents the bonus type. Possible values are `bonusplus`, `personal`, `promo` and `discount_club`.
-    pub bonus_type: Option<Bonus>,
-    /// Represents the sender's card token.
-    pub card_token: Option<String>,
-    /// Represents the commission charged to credit.
-    pub commission_credit: Option<f64>,
-    /// Represents the commission charged to debit.
-    pub commission_debit: Option<f64>,
-    /// Represents the payment creation date.
-    #[serde(rename = "create_date")]
-    pub creation_date: Option<u64>,
-    /// Represents the currency of the payment.
-    pub currency: Option<String>,
-    /// Represents the currency used for credit.
-    pub currency_credit: Option<Currency>,
-    /// Represents the currency used for debit.
-    pub currency_debit: Option<Currency>,
-    /// Represents the payment description.
-    pub description: Option<String>,
-    /// Represents the end date of the payment.
-    pub end_date: Option<u64>,
-    /// Represents the IP address of a sender.
-    pub ip: Option<String>,
-    /// Indicates whether a transaction passed with 3DS.
-    pub is_3ds: Option<bool>,
-    /// Represents the Id of an order in the LiqPay system.
-    pub liqpay_order_id: Option<String>,
-    /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
-    /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
-    pub mpi_eci: Option<MpiEci>,
-    /// Represents ACS value after authentication, returned by the issuer.
-    pub mpi_cres: Option<String>,
-    /// Represents the identifier of an order.
-    pub order_id: Option<String>,
-    /// Represents the identifier of a payment.
-    pub payment_id: Option<u64>,
-    /// Represents the payment type. Possible values are `card` - paid by card,
-    /// `privat24` - account in the Privat24 system, `moment_part` - installment,
-    /// `cash` - paid in cash, `invoice` - invoiced to an email,
-    /// `qr` - paid by scanning a QR code.
-    #[serde(rename = "paytype")]
-    pub pay_type: Option<PayType>,
-    /// Represents the public key of the shop.
-    pub public_key: Option<String>,
-    /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for credit.
-    #[serde(rename = "rrn_credit")]
-    pub retrieval_reference_number_credit: Option<String>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for debit.
-    #[serde(rename = "rrn_debit")]
-    pub retrieval_reference_number_debit: Option<String>,
-    /// Represents the sender's bonus amount.
-    pub sender_bonus: Option<f64>,
-    /// Represents the sender's bank.
-    pub sender_card_bank: Option<String>,
-    /// Represents the sender's card country code in the ISO-3166 format.
-    pub sender_card_country: Option<u16>,
-    /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
-    pub sender_card_mask: Option<String>,
-    /// Represents the sender's card type - either VISA or MasterCard.
-    pub sender_card_type: Option<String>,
-    /// Represents the sender's commission amount.
-    pub sender_commission: Option<f64>,
-    /// Represents the sender's first name.
-    pub sender_first_name: Option<String>,
-    /// Represents the sender's last name.
-    pub sender_last_name: Option<String>,
-    /// Represents the sender's phone number.
-    pub sender_phone: Option<String>,
-    /// Represents the identifier of a transaction in LiqPay.
-    pub transaction_id: Option<u64>,
-    /// Represents the type of an operation.
-    #[serde(rename = "type")]
-    pub operation_type: Option<String>,
-    /// Represents the API version.
-    pub version: Option<Version>,
-    /// Represents the language of the payment.
-    pub language: Option<Language>,
-    /// Represents the phone number used for confirmation via a one-time password.
-    pub confirm_phone: Option<String>,
-    /// Holds an error code.
-    #[serde(rename = "err_code")]
-    pub error_code: Option<String>,
-    /// Holds an error description.
-    #[serde(rename = "err_description")]
-    pub error_description: Option<String>,
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
 }
 
 impl LiqPayResponse for CardPaymentResponse {}
+
+#[cfg(test)]
+mod tests {
+    use iso3166::Country;
+    use serde_json::{Value, json};
+
+    use super::{CardPaymentRequest, CardPaymentResponse};
+    use crate::common::enums::{Currency, MpiEci};
+    use crate::common::validation::{CUSTOMER_MAX_LENGTH, DESCRIPTION_MAX_LENGTH};
+    use crate::error::LiqPayError;
+    use crate::internet_acquiring::common::ElectronicCommerceIndicator;
+
+    fn request() -> CardPaymentRequest {
+        described("description")
+    }
+
+    fn described(description: &str) -> CardPaymentRequest {
+        CardPaymentRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            description.to_owned(),
+        )
+    }
+
+    fn serialize(request: CardPaymentRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn recurring_flags_are_sent_under_their_own_keys() {
+        let merchant_initiated = serialize(request().merchant_initiated());
+        let customer_initiated = serialize(request().customer_initiated());
+        let recurring = serialize(request().recurring());
+        let recurring_by_token = serialize(request().recurring_by_token());
+        let unset = serialize(request());
+
+        assert_eq!(merchant_initiated["is_recurring"], true);
+        assert!(merchant_initiated.get("recurring").is_none());
+        assert_eq!(customer_initiated["is_recurring"], false);
+        assert_eq!(recurring["recurring"], true);
+        assert!(recurring.get("is_recurring").is_none());
+        assert_eq!(recurring_by_token["recurringbytoken"], "1");
+        assert!(unset.get("is_recurring").is_none());
+        assert!(unset.get("recurring").is_none());
+    }
+
+    #[test]
+    fn debug_output_hides_card_number_and_cvv() {
+        let debug = format!("{:?}", request().cvv("987".to_owned()));
+
+        assert!(!debug.contains("4242424242424242"));
+        assert!(!debug.contains("987"));
+        assert!(debug.contains("4242"));
+    }
+
+    #[test]
+    fn mpi_eci_is_sent_as_its_code() {
+        assert_eq!(
+            serialize(request().mpi_eci(MpiEci::Success3Ds))["mpi_eci"],
+            "5"
+        );
+        assert_eq!(
+            serialize(request().mpi_eci(MpiEci::NotSupported3Ds))["mpi_eci"],
+            "6"
+        );
+        assert_eq!(
+            serialize(request().mpi_eci(MpiEci::Without3Ds))["mpi_eci"],
+            "7"
+        );
+        assert!(serialize(request()).get("mpi_eci").is_none());
+    }
+
+    #[test]
+    fn unknown_response_fields_are_kept_in_extra() {
+        let response: CardPaymentResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "new_field": { "nested": 1 },
+        }))
+        .unwrap();
+
+        assert_eq!(response.payment.extra["new_field"], json!({ "nested": 1 }));
+        assert!(!response.payment.extra.contains_key("status"));
+    }
+
+    #[test]
+    fn description_over_limit_is_rejected() {
+        let at_limit = "ї".repeat(DESCRIPTION_MAX_LENGTH);
+        let over_limit = "a".repeat(DESCRIPTION_MAX_LENGTH + 1);
+
+        assert!(described(&at_limit).build().is_ok());
+        assert!(matches!(
+            described(&over_limit).build(),
+            Err(LiqPayError::Validation(message)) if message.contains("description")
+        ));
+    }
+
+    #[test]
+    fn sender_country_is_sent_as_numeric_code() {
+        let ukraine = serialize(request().sender_country_code(Country::from_alpha2("UA").unwrap()));
+        let usa = serialize(request().sender_country_code(Country::from_alpha3("USA").unwrap()));
+
+        assert_eq!(ukraine["sender_country_code"], "804");
+        assert_eq!(usa["sender_country_code"], "840");
+    }
+
+    #[test]
+    fn hold_keeps_amount_card_and_sender_fields() {
+        let payment = request()
+            .cvv("123".to_owned())
+            .sender_first_name("Taras".to_owned())
+            .sender_email("taras@example.com".to_owned())
+            .sender_country_code(Country::from_alpha2("UA").unwrap());
+        let expected = serde_json::to_value(&payment).unwrap();
+
+        let hold = serde_json::to_value(payment.into_hold()).unwrap();
+
+        assert_eq!(hold["action"], "hold");
+        for field in [
+            "public_key",
+            "amount",
+            "currency",
+            "order_id",
+            "description",
+            "card",
+            "card_exp_month",
+            "card_exp_year",
+            "card_cvv",
+            "sender_first_name",
+            "sender_email",
+            "sender_country_code",
+        ] {
+            assert!(!expected[field].is_null(), "{field}");
+            assert_eq!(hold[field], expected[field], "{field}");
+        }
+    }
+
+    #[test]
+    fn customer_over_limit_is_rejected() {
+        let customer = "c".repeat(CUSTOMER_MAX_LENGTH + 1);
+
+        assert!(matches!(
+            request().customer(customer).build(),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn customer_within_limit_is_accepted() {
+        let customer = "c".repeat(CUSTOMER_MAX_LENGTH);
+
+        assert!(request().customer(customer).build().is_ok());
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = request().sender_email("taras@example.com".to_owned());
+        let invalid = request().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serialize(valid.build().unwrap())["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+
+    #[test]
+    fn eci_is_sent_as_its_former_string_value() {
+        let indicators = [
+            (ElectronicCommerceIndicator::MasterCardAuthenticated, "02"),
+            (ElectronicCommerceIndicator::VisaAuthenticated, "05"),
+            (
+                ElectronicCommerceIndicator::MasterCardNotAuthenticated,
+                "06",
+            ),
+            (ElectronicCommerceIndicator::VisaNotAuthenticated, "07"),
+        ];
+
+        for (indicator, eci) in indicators {
+            assert_eq!(
+                serialize(request().electronic_commerce_indicator(indicator))["eci"],
+                eci
+            );
+        }
+        assert!(serialize(request()).get("eci").is_none());
+    }
+}
@@ -1,17 +1,27 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::enums::{
+    Action, Currency, Language, MpiEci, PayType, Prepare, Result, Status, Version,
+};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_max_length, check_order_id, check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::DetailAddenda;
 
 /// Represents a request to perform a cash payment.
 #[derive(Serialize, Debug)]
 pub struct CashPaymentRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
     description: String,
@@ -24,15 +34,21 @@ pub struct CashPaymentRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    prepare: Option<char>,
+    prepare: Option<Prepare>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     split_rules: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
-    #[serde(rename = "dae", skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
     info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,23 +61,38 @@ pub struct CashPaymentRequest {
     product_url: Option<String>,
 }
 
-impl LiqPayRequest<CashPaymentResponse, Sha3_256> for CashPaymentRequest {}
+impl LiqPayRequest<CashPaymentResponse, Sha3_256> for CashPaymentRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl CashPaymentRequest {
     /// Constructs a new cash payment request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::PayCash,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
             ip: None,
@@ -69,6 +100,7 @@ impl CashPaymentRequest {
             expiration_date: None,
             language: None,
             prepare: None,
+            result_url: None,
             server_url: None,
             split_rules: None,
             customer: None,
@@ -81,6 +113,23 @@ impl CashPaymentRequest {
         }
     }
 
+    /// Constructs a new cash payment request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the customer's IP address.
     pub fn ip(mut self, ip: String) -> Self {
         self.ip = Some(ip);
@@ -108,7 +157,21 @@ impl CashPaymentRequest {
     /// Sets the preliminary preparation of the payment. The mode allows to determine data completeness,
     /// whether 3DS is required or limit is exceeded. The funds are not debited.
     pub fn prepare(mut self) -> Self {
-        self.prepare = Some('1');
+        self.prepare = Some(Prepare::Enable);
+        self
+    }
+
+    /// Sets the preliminary preparation of the payment in the tariffs mode, which returns
+    /// the commissions of the payment. The funds are not debited.
+    pub fn prepare_tariffs(mut self) -> Self {
+        self.prepare = Some(Prepare::Tariffs);
+        self
+    }
+
+    /// Sets the URL of the shop, to which the customer is redirected after completing a purchase.
+    /// Must not exceed 510 characters.
+    pub fn result_url(mut self, url: String) -> Self {
+        self.result_url = Some(url);
         self
     }
 
@@ -134,7 +197,7 @@ impl CashPaymentRequest {
 
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -144,6 +207,12 @@ impl CashPaymentRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
     /// Sets the product category. Must not exceed 25 symbols.
     pub fn product_category(mut self, category: String) -> Self {
         self.product_category = Some(category);
@@ -167,10 +236,20 @@ impl CashPaymentRequest {
         self.product_url = Some(url);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a cash payment operation.
-#[derive(Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CashPaymentResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -186,18 +265,25 @@ pub struct CashPaymentResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the phone number used for confirmation via a one-time password.
     pub confirm_phone: Option<String>,
@@ -217,6 +303,7 @@ pub struct CashPaymentResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -236,10 +323,13 @@ pub struct CashPaymentResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,
@@ -263,3 +353,32 @@ pub struct CashPaymentResponse {
 }
 
 impl LiqPayResponse for CashPaymentResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::CashPaymentRequest;
+    use crate::common::enums::Currency;
+
+    fn request() -> CashPaymentRequest {
+        CashPaymentRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    fn serialize(request: CashPaymentRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn result_url_is_sent_only_when_set() {
+        let set = serialize(request().result_url("https://shop.example.com/result".to_owned()));
+
+        assert_eq!(set["result_url"], "https://shop.example.com/result");
+        assert!(serialize(request()).get("result_url").is_none());
+    }
+}
@@ -1,5 +1,10 @@
 use base64::{Engine, engine::general_purpose};
-use serde::Serialize;
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::common::amount::Amount;
+use crate::common::validation::check_email;
+use crate::error::LiqPayError;
 
 /// Represents the transportation details.
 #[derive(Serialize, Default, Debug)]
@@ -77,14 +82,32 @@ impl DetailAddenda {
     }
 
     /// Encodes transportation details to base64 format.
-    pub fn to_base64(&self) -> String {
-        let serialized = serde_json::to_string(&self).unwrap_or(String::new());
+    pub fn to_base64(&self) -> Result<String, serde_json::Error> {
+        let serialized = serde_json::to_string(&self)?;
         let base64 = general_purpose::STANDARD.encode(serialized);
 
-        base64
+        Ok(base64)
+    }
+
+    /// Serializes transportation details of a request as a base64-encoded string.
+    /// An encoding error fails the serialization of the whole request.
+    pub(crate) fn serialize_base64<S: Serializer>(
+        detail: &Option<Self>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match detail {
+            Some(detail) => {
+                let base64 = detail.to_base64().map_err(S::Error::custom)?;
+                serializer.serialize_str(&base64)
+            }
+            None => serializer.serialize_none(),
+        }
     }
 }
 
+/// The tolerance used when comparing an item's cost with its amount multiplied by its price.
+const COST_TOLERANCE: f64 = 0.005;
+
 /// Represents a data about a fiscalization item.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct Item {
@@ -104,6 +127,41 @@ impl Item {
             price,
         }
     }
+
+    fn check_cost(&self) -> Result<(), LiqPayError> {
+        let expected = self.amount as f64 * self.price;
+
+        if (self.cost - expected).abs() < COST_TOLERANCE {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "the cost of the item `{}` must equal its amount multiplied by its price",
+                self.id
+            )))
+        }
+    }
+}
+
+/// Represents an invoice line item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Good {
+    name: String,
+    count: u32,
+    #[serde(rename = "amount")]
+    price: Amount,
+    unit: String,
+}
+
+impl Good {
+    /// Constructs a new invoice line item. The price is set per unit of a good.
+    pub fn new(name: String, count: u32, price: impl Into<Amount>, unit: String) -> Self {
+        Self {
+            name,
+            count,
+            price: price.into(),
+            unit,
+        }
+    }
 }
 
 /// Represents fiscalization data.
@@ -135,6 +193,28 @@ impl RroInfo {
         self.delivery_emails = Some(emails);
         self
     }
+
+    /// Validates fiscalization data. Delivery emails must be well-formed and not empty,
+    /// and the cost of each item must equal its amount multiplied by its price.
+    pub fn build(self) -> Result<Self, LiqPayError> {
+        if let Some(emails) = &self.delivery_emails {
+            if emails.is_empty() {
+                return Err(LiqPayError::Validation(String::from(
+                    "`delivery_emails` must not be empty",
+                )));
+            }
+
+            for email in emails {
+                check_email("delivery_emails", email)?;
+            }
+        }
+
+        for item in self.items.iter().flatten() {
+            item.check_cost()?;
+        }
+
+        Ok(self)
+    }
 }
 
 /// Represents Electronic Commerce Indicator.
@@ -149,3 +229,163 @@ pub enum ElectronicCommerceIndicator {
     #[serde(rename = "07")]
     VisaNotAuthenticated,
 }
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, engine::general_purpose};
+    use serde::Serialize;
+    use serde_json::{Value, json};
+
+    use super::{DetailAddenda, Item, RroInfo};
+    use crate::common::enums::Currency;
+    use crate::error::LiqPayError;
+    use crate::internet_acquiring::card::CardPaymentRequest;
+    use crate::internet_acquiring::cash::CashPaymentRequest;
+    use crate::internet_acquiring::qr_code::DynamicQrCodeRequest;
+    use crate::internet_acquiring::subscription::{SubscribePeriodicity, SubscribeRequest};
+    use crate::internet_acquiring::token::TokenPaymentRequest;
+    use crate::internet_acquiring::two_step::FundsBlockingRequest;
+
+    const PUBLIC_KEY: &str = "public_key";
+    const VALUE: (f64, Currency) = (100.0, Currency::UAH);
+
+    fn addenda() -> DetailAddenda {
+        DetailAddenda::new().airline("Airline".to_owned())
+    }
+
+    fn addenda_keys(request: impl Serialize) -> (bool, bool) {
+        let serialized_request: Value = serde_json::to_value(request).unwrap();
+
+        (
+            serialized_request.get("dae").is_some(),
+            serialized_request.get("detail_addenda").is_some(),
+        )
+    }
+
+    #[test]
+    fn detail_addenda_is_sent_as_dae_by_every_request() {
+        let card = || "4242424242424242".to_owned();
+        let month = || "12".to_owned();
+        let year = || "30".to_owned();
+        let order_id = || "order".to_owned();
+        let description = || "description".to_owned();
+
+        let keys = [
+            addenda_keys(
+                CardPaymentRequest::with_value(
+                    PUBLIC_KEY,
+                    VALUE,
+                    card(),
+                    month(),
+                    year(),
+                    order_id(),
+                    description(),
+                )
+                .detail_addenda(addenda()),
+            ),
+            addenda_keys(
+                FundsBlockingRequest::card_with_value(
+                    PUBLIC_KEY,
+                    VALUE,
+                    card(),
+                    month(),
+                    year(),
+                    order_id(),
+                    description(),
+                )
+                .detail_addenda(addenda()),
+            ),
+            addenda_keys(
+                CashPaymentRequest::with_value(PUBLIC_KEY, VALUE, order_id(), description())
+                    .detail_addenda(addenda()),
+            ),
+            addenda_keys(
+                SubscribeRequest::with_value(
+                    PUBLIC_KEY,
+                    VALUE,
+                    card(),
+                    month(),
+                    year(),
+                    order_id(),
+                    description(),
+                    "2030-01-01 00:00:00".to_owned(),
+                    SubscribePeriodicity::Month,
+                )
+                .detail_addenda(addenda()),
+            ),
+            addenda_keys(
+                TokenPaymentRequest::with_value(
+                    PUBLIC_KEY,
+                    VALUE,
+                    "card_token".to_owned(),
+                    order_id(),
+                    description(),
+                )
+                .detail_addenda(addenda()),
+            ),
+            addenda_keys(
+                DynamicQrCodeRequest::with_value(PUBLIC_KEY, VALUE, order_id(), description())
+                    .detail_addenda(addenda()),
+            ),
+        ];
+
+        assert!(keys.iter().all(|&keys| keys == (true, false)));
+    }
+
+    #[test]
+    fn detail_addenda_round_trips_through_base64() {
+        let addenda = DetailAddenda::new()
+            .airline("Airline".to_owned())
+            .ticket_number("1234567890".to_owned())
+            .passenger_name("John Doe".to_owned())
+            .flight_number("PS101".to_owned())
+            .origin_city("Kyiv".to_owned())
+            .destination_city("Lviv".to_owned())
+            .departure_date(20300101);
+
+        let encoded = addenda.to_base64().unwrap();
+        let decoded: Value =
+            serde_json::from_slice(&general_purpose::STANDARD.decode(encoded).unwrap()).unwrap();
+
+        assert_eq!(
+            decoded,
+            json!({
+                "airLine": "Airline",
+                "ticketNumber": "1234567890",
+                "passengerName": "John Doe",
+                "flightNumber": "PS101",
+                "originCity": "Kyiv",
+                "destinationCity": "Lviv",
+                "departureDate": 20300101,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_or_empty_delivery_emails_are_rejected() {
+        let invalid = RroInfo::new()
+            .emails(vec![
+                "customer@example.com".to_owned(),
+                "customer".to_owned(),
+            ])
+            .build();
+        let empty = RroInfo::new().emails(Vec::new()).build();
+
+        assert!(matches!(invalid, Err(LiqPayError::Validation(_))));
+        assert!(matches!(empty, Err(LiqPayError::Validation(_))));
+    }
+
+    #[test]
+    fn item_cost_must_equal_amount_times_price() {
+        let mismatched = RroInfo::new()
+            .items(vec![Item::new(1, 3, 30.0, 12.0)])
+            .build();
+        let matching = RroInfo::new()
+            .items(vec![Item::new(1, 3, 0.3, 0.1), Item::new(2, 2, 24.0, 12.0)])
+            .emails(vec!["customer@example.com".to_owned()])
+            .build();
+
+        assert!(matches!(mismatched, Err(LiqPayError::Validation(_))));
+        assert!(matching.is_ok());
+    }
+}
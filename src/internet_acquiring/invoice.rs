@@ -1,21 +1,30 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
 use sha1::Sha1;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::enums::{Action, Currency, Language, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
-use crate::internet_acquiring::common::RroInfo;
+use crate::common::validation::{
+    INVOICE_DESCRIPTION_MAX_LENGTH, check_date_time, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
+use crate::internet_acquiring::common::{Good, RroInfo};
 
 /// Represents a request to initiate an invoice sending operation.
 #[derive(Debug, Serialize)]
 pub struct SendInvoiceRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
-    email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -27,7 +36,7 @@ pub struct SendInvoiceRequest {
     #[serde(rename = "expired_date", skip_serializing_if = "Option::is_none")]
     expiration_date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    goods: Option<String>,
+    goods: Option<Vec<Good>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -36,27 +45,95 @@ pub struct SendInvoiceRequest {
     server_url: Option<String>,
 }
 
-impl LiqPayRequest<SendInvoiceResponse, Sha3_256> for SendInvoiceRequest {}
+impl LiqPayRequest<SendInvoiceResponse, Sha3_256> for SendInvoiceRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl SendInvoiceRequest {
-    /// Constructs a new invoice sending request.
+    /// Constructs a new request to send an invoice to an email.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
+        order_id: String,
+        email: String,
+    ) -> Self {
+        Self::with_contact(public_key, value.into(), order_id, Some(email), None)
+    }
+
+    /// Constructs a new request to send an invoice to an email.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
     pub fn new(
         public_key: impl Into<String>,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: Currency,
         order_id: String,
         email: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            email,
+        )
+    }
+
+    /// Constructs a new request to send an invoice to a phone number.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_phone_with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
+        order_id: String,
+        phone: String,
+    ) -> Self {
+        Self::with_contact(public_key, value.into(), order_id, None, Some(phone))
+    }
+
+    /// Constructs a new request to send an invoice to a phone number.
+    #[deprecated(note = "use `by_phone_with_value`, which pairs the amount with its currency")]
+    pub fn by_phone(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        order_id: String,
+        phone: String,
+    ) -> Self {
+        Self::by_phone_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            phone,
+        )
+    }
+
+    fn with_contact(
+        public_key: impl Into<String>,
+        value: MonetaryValue,
+        order_id: String,
+        email: Option<String>,
+        phone: Option<String>,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::SendInvoice,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             email,
             description: None,
-            phone: None,
+            phone,
             rro_info: None,
             action_payment: None,
             expiration_date: None,
@@ -67,12 +144,18 @@ impl SendInvoiceRequest {
         }
     }
 
-    /// Sets a description.
+    /// Sets a description. Must not exceed 1000 characters.
     pub fn description(mut self, description: String) -> Self {
         self.description = Some(description);
         self
     }
 
+    /// Sets an email.
+    pub fn email(mut self, email: String) -> Self {
+        self.email = Some(email);
+        self
+    }
+
     /// Sets a phone number.
     pub fn phone(mut self, phone: String) -> Self {
         self.phone = Some(phone);
@@ -110,13 +193,14 @@ impl SendInvoiceRequest {
     }
 
     /// Sets the date and time (in UTC) until which a customer is able to pay an invoice.
+    /// The format is YYYY-MM-DD HH:MM:SS.
     pub fn expiration_date(mut self, date: String) -> Self {
         self.expiration_date = Some(date);
         self
     }
 
-    /// Sets the goods.
-    pub fn goods(mut self, goods: String) -> Self {
+    /// Sets the goods, the invoice is issued for.
+    pub fn goods(mut self, goods: Vec<Good>) -> Self {
         self.goods = Some(goods);
         self
     }
@@ -140,10 +224,39 @@ impl SendInvoiceRequest {
         self.server_url = Some(url);
         self
     }
+
+    /// Validates the request before it is sent. Either an email or a phone number must be set,
+    /// and the expiration date must be a valid UTC date and time.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        let has_contact = [&self.email, &self.phone]
+            .into_iter()
+            .flatten()
+            .any(|contact| !contact.trim().is_empty());
+
+        if !has_contact {
+            return Err(LiqPayError::Validation(String::from(
+                "either `email` or `phone` must be set",
+            )));
+        }
+
+        if let Some(description) = &self.description {
+            check_max_length("description", description, INVOICE_DESCRIPTION_MAX_LENGTH)?;
+        }
+
+        if let Some(date) = &self.expiration_date {
+            check_date_time("expired_date", date)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to an invoice sending operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SendInvoiceResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -156,6 +269,7 @@ pub struct SendInvoiceResponse {
     /// `auth` - card preauthentication, `regular` - regular payment.
     pub action: Option<Action>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payment currency.
     pub currency: Option<String>,
@@ -186,28 +300,54 @@ impl LiqPayResponse for SendInvoiceResponse {}
 /// Represents a request to cancel an invoice sending operation.
 #[derive(Debug, Serialize)]
 pub struct CancelInvoiceRequest {
-    version: Version,
     public_key: String,
     action: Action,
     order_id: String,
 }
 
-impl LiqPayRequest<CancelInvoiceResponse, Sha3_256> for CancelInvoiceRequest {}
+impl LiqPayRequest<CancelInvoiceResponse, Sha3_256> for CancelInvoiceRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl CancelInvoiceRequest {
-    /// Constructs a new invoice cancellation request.
+    /// Constructs a new invoice cancellation request. LiqPay cancels an invoice by its `order_id` only,
+    /// the invoice `id` and `token` cannot be used instead.
     pub fn new(public_key: impl Into<String>, order_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::CancelInvoice,
             public_key: public_key.into(),
             order_id,
         }
     }
+
+    /// Constructs a new request to cancel the invoice sent by an invoice sending operation.
+    /// Returns `None` if the response does not hold the `order_id` of the invoice.
+    pub fn for_invoice(
+        public_key: impl Into<String>,
+        invoice: &SendInvoiceResponse,
+    ) -> Option<Self> {
+        invoice
+            .order_id
+            .clone()
+            .map(|order_id| Self::new(public_key, order_id))
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to an invoice cancellation operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CancelInvoiceResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -226,7 +366,6 @@ impl LiqPayResponse for CancelInvoiceResponse {}
 /// Represents a request to get invoice units.
 #[derive(Debug, Serialize)]
 pub struct InvoiceUnitsRequest {
-    version: Version,
     action: Action,
     public_key: String,
     #[serde(rename = "hide_name_lang", skip_serializing_if = "Option::is_none")]
@@ -235,14 +374,23 @@ pub struct InvoiceUnitsRequest {
     language: Option<Language>,
 }
 
-impl LiqPayRequest<InvoiceUnitsResponse, Sha1> for InvoiceUnitsRequest {}
+impl LiqPayRequest<InvoiceUnitsResponse, Sha1> for InvoiceUnitsRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl InvoiceUnitsRequest {
     /// Constructs a new request to get invoice units by language.
     /// The result contains only items in the specified locale.
     pub fn by_language(public_key: impl Into<String>, language: Language) -> Self {
         Self {
-            version: Version::Three,
             action: Action::GetInvoiceUnitsByLanguage,
             public_key: public_key.into(),
             language: Some(language),
@@ -253,7 +401,6 @@ impl InvoiceUnitsRequest {
     /// Constructs a new request to get full invoice units in both the Ukrainian and English locales.
     pub fn full(public_key: impl Into<String>) -> Self {
         Self {
-            version: Version::Three,
             action: Action::GetInvoiceUnits,
             public_key: public_key.into(),
             hide_language_name: None,
@@ -274,7 +421,7 @@ impl InvoiceUnitsRequest {
 }
 
 /// Represents an invoice unit.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Unit {
     /// Represents the identifier of a unit.
     pub id: u32,
@@ -295,7 +442,7 @@ pub struct Unit {
 }
 
 /// Represents the response to a getting invoice units operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct InvoiceUnitsResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -312,3 +459,158 @@ pub struct InvoiceUnitsResponse {
 }
 
 impl LiqPayResponse for InvoiceUnitsResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{CancelInvoiceRequest, SendInvoiceRequest, SendInvoiceResponse};
+    use crate::common::enums::Currency;
+    use crate::common::validation::INVOICE_DESCRIPTION_MAX_LENGTH;
+    use crate::error::LiqPayError;
+    use crate::internet_acquiring::common::Good;
+
+    fn by_email(email: &str) -> SendInvoiceRequest {
+        SendInvoiceRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "order".to_owned(),
+            email.to_owned(),
+        )
+    }
+
+    fn by_phone(phone: &str) -> SendInvoiceRequest {
+        SendInvoiceRequest::by_phone_with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "order".to_owned(),
+            phone.to_owned(),
+        )
+    }
+
+    #[test]
+    fn invoice_without_contact_is_rejected() {
+        assert!(matches!(
+            by_email("").build(),
+            Err(LiqPayError::Validation(_))
+        ));
+        assert!(matches!(
+            by_phone("  ").build(),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn invoice_with_email_or_phone_is_accepted() {
+        assert!(by_email("customer@example.com").build().is_ok());
+        assert!(by_phone("380950000001").build().is_ok());
+        assert!(
+            by_phone("")
+                .email("customer@example.com".to_owned())
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn malformed_expiration_date_is_rejected() {
+        let request = || by_email("customer@example.com");
+
+        assert!(
+            request()
+                .expiration_date("2030-13-01 00:00:00".to_owned())
+                .build()
+                .is_err()
+        );
+        assert!(
+            request()
+                .expiration_date("2030/01/01 00:00:00".to_owned())
+                .build()
+                .is_err()
+        );
+        assert!(
+            request()
+                .expiration_date("2030-01-01".to_owned())
+                .build()
+                .is_err()
+        );
+        assert!(
+            request()
+                .expiration_date("2030-01-31 23:59:59".to_owned())
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn goods_are_sent_as_documented_array() {
+        let request = by_email("customer@example.com").goods(vec![
+            Good::new("phone".to_owned(), 2, 100.0, "pcs".to_owned()),
+            Good::new("case".to_owned(), 1, 9.99, "pcs".to_owned()),
+        ]);
+
+        let serialized = serde_json::to_value(request).unwrap();
+
+        assert_eq!(
+            serialized["goods"],
+            json!([
+                { "name": "phone", "count": 2, "amount": 100.0, "unit": "pcs" },
+                { "name": "case", "count": 1, "amount": 9.99, "unit": "pcs" },
+            ])
+        );
+    }
+
+    #[test]
+    fn description_over_invoice_limit_is_rejected() {
+        let request = |length: usize| {
+            by_email("customer@example.com")
+                .description("a".repeat(length))
+                .build()
+        };
+
+        assert!(request(INVOICE_DESCRIPTION_MAX_LENGTH).is_ok());
+        assert!(matches!(
+            request(INVOICE_DESCRIPTION_MAX_LENGTH + 1),
+            Err(LiqPayError::Validation(message)) if message.contains("description")
+        ));
+    }
+
+    fn sent_invoice(order_id: Option<&str>) -> SendInvoiceResponse {
+        serde_json::from_value(json!({
+            "result": "ok",
+            "status": "invoice_wait",
+            "id": 123456,
+            "token": "token",
+            "order_id": order_id,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn cancellation_is_built_from_order_id() {
+        let request = CancelInvoiceRequest::new("public_key", "order".to_owned())
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            json!({
+                "public_key": "public_key",
+                "action": "invoice_cancel",
+                "order_id": "order",
+            })
+        );
+    }
+
+    #[test]
+    fn cancellation_is_built_from_sent_invoice() {
+        let request = CancelInvoiceRequest::for_invoice("public_key", &sent_invoice(Some("order")));
+
+        assert_eq!(
+            serde_json::to_value(request.unwrap()).unwrap(),
+            serde_json::to_value(CancelInvoiceRequest::new("public_key", "order".to_owned()))
+                .unwrap()
+        );
+        assert!(CancelInvoiceRequest::for_invoice("public_key", &sent_invoice(None)).is_none());
+    }
+}
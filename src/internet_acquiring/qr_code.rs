@@ -1,17 +1,26 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, MpiEci, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::enums::{Action, Currency, Language, MpiEci, Prepare, Result, Status, Version};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_email, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::DetailAddenda;
 
 /// Represents a request to perform a payment by a dynamic QR code.
 #[derive(Serialize, Debug)]
 pub struct DynamicQrCodeRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     description: String,
     order_id: String,
@@ -20,10 +29,12 @@ pub struct DynamicQrCodeRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    prepare: Option<char>,
+    prepare: Option<Prepare>,
     #[serde(rename = "recurringbytoken", skip_serializing_if = "Option::is_none")]
     recurring_by_token: Option<char>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     split_rules: Option<String>,
@@ -31,8 +42,14 @@ pub struct DynamicQrCodeRequest {
     split_tickets_only: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
-    #[serde(rename = "dae", skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender_email: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
     info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,32 +62,49 @@ pub struct DynamicQrCodeRequest {
     product_url: Option<String>,
 }
 
-impl LiqPayRequest<DynamicQrCodeResponse, Sha3_256> for DynamicQrCodeRequest {}
+impl LiqPayRequest<DynamicQrCodeResponse, Sha3_256> for DynamicQrCodeRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl DynamicQrCodeRequest {
     /// Constructs a new dynamic QR code payment request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::PayQrCode,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
             ip: None,
             language: None,
             prepare: None,
+            result_url: None,
             server_url: None,
             split_rules: None,
             split_tickets_only: None,
             customer: None,
+            sender_email: None,
             detail_addenda: None,
             info: None,
             product_category: None,
@@ -81,6 +115,23 @@ impl DynamicQrCodeRequest {
         }
     }
 
+    /// Constructs a new dynamic QR code payment request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the customer's IP address.
     pub fn ip(mut self, ip: String) -> Self {
         self.ip = Some(ip);
@@ -96,7 +147,14 @@ impl DynamicQrCodeRequest {
     /// Sets the preliminary preparation of the payment. The mode allows to determine data completeness,
     /// whether 3DS is required or limit is exceeded. The funds are not debited.
     pub fn prepare(mut self) -> Self {
-        self.prepare = Some('1');
+        self.prepare = Some(Prepare::Enable);
+        self
+    }
+
+    /// Sets the preliminary preparation of the payment in the tariffs mode, which returns
+    /// the commissions of the payment. The funds are not debited.
+    pub fn prepare_tariffs(mut self) -> Self {
+        self.prepare = Some(Prepare::Tariffs);
         self
     }
 
@@ -106,6 +164,13 @@ impl DynamicQrCodeRequest {
         self
     }
 
+    /// Sets the URL of the shop, to which the customer is redirected after completing a purchase.
+    /// Must not exceed 510 characters.
+    pub fn result_url(mut self, url: String) -> Self {
+        self.result_url = Some(url);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -133,9 +198,15 @@ impl DynamicQrCodeRequest {
         self
     }
 
+    /// Sets the sender's email.
+    pub fn sender_email(mut self, email: String) -> Self {
+        self.sender_email = Some(email);
+        self
+    }
+
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -145,6 +216,12 @@ impl DynamicQrCodeRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
     /// Sets the product category. Must not exceed 25 symbols.
     pub fn product_category(mut self, category: String) -> Self {
         self.product_category = Some(category);
@@ -168,10 +245,25 @@ impl DynamicQrCodeRequest {
         self.product_url = Some(url);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a dynamic QR code operation.
-#[derive(Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct DynamicQrCodeResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -187,22 +279,29 @@ pub struct DynamicQrCodeResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
     /// Represents the authorization code for debit.
     pub authcode_debit: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the currency of the payment.
     pub currency: Option<String>,
@@ -220,6 +319,7 @@ pub struct DynamicQrCodeResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -233,12 +333,16 @@ pub struct DynamicQrCodeResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the generated QR code.
+    #[serde(flatten, with = "crate::common::de::qr_code")]
     pub qr_code: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the identifier of a transaction in LiqPay.
     pub transaction_id: Option<u64>,
@@ -257,45 +361,107 @@ pub struct DynamicQrCodeResponse {
 
 impl LiqPayResponse for DynamicQrCodeResponse {}
 
+impl DynamicQrCodeResponse {
+    /// Renders the generated QR code to a PNG image.
+    /// Returns `None` if the QR code is absent or cannot be rendered.
+    #[cfg(feature = "qrcode")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "qrcode")))]
+    pub fn qr_png(&self) -> Option<Vec<u8>> {
+        self.qr_code.as_deref().and_then(render_png)
+    }
+}
+
 /// Represents a request to perform a payment by a static QR code.
 #[derive(Debug, Serialize)]
 pub struct StaticQrCodeRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
     description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<Language>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     final_date: Option<String>,
 }
 
-impl LiqPayRequest<StaticQrCodeResponse, Sha3_256> for StaticQrCodeRequest {}
+impl LiqPayRequest<StaticQrCodeResponse, Sha3_256> for StaticQrCodeRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 /// Represents the response to a static QR code operation.
 impl StaticQrCodeRequest {
     /// Constructs a new static QR code payment request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::CreateQrCode,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
+            language: None,
+            result_url: None,
             server_url: None,
             final_date: None,
         }
     }
 
+    /// Constructs a new static QR code payment request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            description,
+        )
+    }
+
+    /// Sets the customer's language. Allowed values are `uk` - Ukrainian and `en` - English.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the URL of the shop, to which the customer is redirected after completing a purchase.
+    /// Must not exceed 510 characters.
+    pub fn result_url(mut self, url: String) -> Self {
+        self.result_url = Some(url);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -308,10 +474,19 @@ impl StaticQrCodeRequest {
         self.final_date = Some(date);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a static QR code operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct StaticQrCodeResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -322,6 +497,7 @@ pub struct StaticQrCodeResponse {
     /// Represent the identifier of a merchant in LiqPay.
     pub shop_id: Option<u32>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the currency of the payment.
     pub currency: Option<Currency>,
@@ -333,7 +509,7 @@ pub struct StaticQrCodeResponse {
     /// Represents the payment date, until which a QR code is valid.
     pub final_date: Option<u64>,
     /// Represents the generated QR code.
-    #[serde(rename = "qrdata")]
+    #[serde(flatten, with = "crate::common::de::qrdata")]
     pub qr_code: Option<String>,
     /// Represents the payment URL.
     pub url: Option<String>,
@@ -346,3 +522,160 @@ pub struct StaticQrCodeResponse {
 }
 
 impl LiqPayResponse for StaticQrCodeResponse {}
+
+impl StaticQrCodeResponse {
+    /// Returns the payment URL of a successful response, or the error LiqPay has replied with otherwise.
+    pub fn payment_url(&self) -> StdResult<&str, LiqPayError> {
+        match (&self.result, self.url.as_deref()) {
+            (Result::Ok, Some(url)) => Ok(url),
+            _ => Err(LiqPayError::Api {
+                code: self.error_code.clone(),
+                description: self.error_description.clone(),
+            }),
+        }
+    }
+
+    /// Renders the generated QR code to a PNG image.
+    /// Returns `None` if the QR code is absent or cannot be rendered.
+    #[cfg(feature = "qrcode")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "qrcode")))]
+    pub fn qr_png(&self) -> Option<Vec<u8>> {
+        self.qr_code.as_deref().and_then(render_png)
+    }
+}
+
+#[cfg(feature = "qrcode")]
+fn render_png(data: &str) -> Option<Vec<u8>> {
+    let code = qrcode::QrCode::new(data.as_bytes()).ok()?;
+    let image = code.render::<image::Luma<u8>>().build();
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png).ok()?;
+
+    Some(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+    use serde_json::{Value, json};
+
+    #[cfg(feature = "qrcode")]
+    use super::DynamicQrCodeResponse;
+    use super::{DynamicQrCodeRequest, StaticQrCodeRequest, StaticQrCodeResponse};
+    use crate::common::enums::{Currency, Language};
+    use crate::error::LiqPayError;
+
+    fn dynamic_request() -> DynamicQrCodeRequest {
+        DynamicQrCodeRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    fn static_request() -> StaticQrCodeRequest {
+        StaticQrCodeRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    fn serialize(request: impl Serialize) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn result_url_and_language_are_sent_only_when_set() {
+        let url = || "https://shop.example.com/result".to_owned();
+        let dynamic = serialize(dynamic_request().result_url(url()));
+        let static_ = serialize(static_request().result_url(url()).language(Language::Uk));
+        let unset = [serialize(dynamic_request()), serialize(static_request())];
+
+        assert_eq!(dynamic["result_url"], url());
+        assert_eq!(static_["result_url"], url());
+        assert_eq!(static_["language"], "uk");
+        assert!(
+            unset
+                .iter()
+                .all(|request| request.get("result_url").is_none())
+        );
+        assert!(unset[1].get("language").is_none());
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn known_qr_data_is_rendered_to_png() {
+        const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+        let qr_data = "https://www.liqpay.ua/en/checkout/qr/1234567890";
+
+        let dynamic: DynamicQrCodeResponse = serde_json::from_value(
+            json!({ "result": "ok", "status": "success", "qr_code": qr_data }),
+        )
+        .unwrap();
+        let static_: StaticQrCodeResponse = serde_json::from_value(
+            json!({ "result": "ok", "status": "success", "qrdata": qr_data }),
+        )
+        .unwrap();
+
+        for png in [dynamic.qr_png(), static_.qr_png()] {
+            assert!(png.unwrap().starts_with(PNG_SIGNATURE));
+        }
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn missing_qr_data_is_not_rendered() {
+        let response: StaticQrCodeResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "success" })).unwrap();
+
+        assert_eq!(response.qr_png(), None);
+    }
+
+    #[test]
+    fn payment_url_is_returned_for_success() {
+        let response: StaticQrCodeResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "url": "https://www.liqpay.ua/en/checkout/qr/1234567890",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.payment_url().unwrap(),
+            "https://www.liqpay.ua/en/checkout/qr/1234567890"
+        );
+    }
+
+    #[test]
+    fn api_error_is_returned_for_error_response() {
+        let response: StaticQrCodeResponse = serde_json::from_value(json!({
+            "result": "error",
+            "status": "error",
+            "err_code": "public_key_not_found",
+            "err_description": "Public key not found",
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            response.payment_url(),
+            Err(LiqPayError::Api { code: Some(code), description: Some(description) })
+                if code == "public_key_not_found" && description == "Public key not found"
+        ));
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = dynamic_request().sender_email("taras@example.com".to_owned());
+        let invalid = dynamic_request().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serialize(valid.build().unwrap())["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+}
@@ -1,36 +1,55 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Result, Status, Version};
+use crate::common::amount::Amount;
+use crate::common::enums::{Action, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{check_order_id, check_positive_amount};
+use crate::error::LiqPayError;
 
 /// Represents a request to initiate a refund operation.
 #[derive(Debug, Serialize)]
 pub struct RefundRequest {
-    version: Version,
     action: Action,
     public_key: String,
     order_id: String,
-    amount: f64,
+    amount: Amount,
 }
 
 impl RefundRequest {
     /// Constructs a new refund request.
-    pub fn new(public_key: impl Into<String>, order_id: String, amount: f64) -> Self {
+    pub fn new(public_key: impl Into<String>, order_id: String, amount: impl Into<Amount>) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Refund,
             public_key: public_key.into(),
             order_id,
-            amount,
+            amount: amount.into(),
         }
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_positive_amount("amount", self.amount)?;
+
+        Ok(self)
+    }
 }
 
-impl LiqPayRequest<RefundResponse, Sha3_256> for RefundRequest {}
+impl LiqPayRequest<RefundResponse, Sha3_256> for RefundRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 /// Represents the response to a refund operation.
-#[derive(Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RefundResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -39,6 +58,7 @@ pub struct RefundResponse {
     pub status: Status,
     /// Indicates whether the operation is a return for future payments or whether it should be performed
     /// from the merchant's account.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub wait_amount: Option<bool>,
     /// Represents the operation type. Possible values are `pay` - payment,
     /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation.
@@ -1,9 +1,23 @@
+use std::result::Result as StdResult;
+
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{
+    Action, Currency, Language, MpiEci, PayType, Prepare, Result, Status, Version,
+};
+use crate::common::responses::PaymentResponse;
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_email, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::DetailAddenda;
 
 /// Represents the subscription periodicity.
@@ -20,13 +34,12 @@ pub enum SubscribePeriodicity {
 }
 
 /// Represents a request to create a subscription.
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 pub struct SubscribeRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
-    card: String,
+    amount: Amount,
+    card: CardNumber,
     card_exp_month: String,
     card_exp_year: String,
     currency: Currency,
@@ -35,7 +48,7 @@ pub struct SubscribeRequest {
     subscribe_date_start: String,
     subscribe_periodicity: SubscribePeriodicity,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ip: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -43,12 +56,14 @@ pub struct SubscribeRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    prepare: Option<char>,
+    prepare: Option<Prepare>,
     #[serde(rename = "recurringbytoken", skip_serializing_if = "Option::is_none")]
     recurring_by_token: Option<char>,
     #[serde(skip_serializing_if = "Option::is_none")]
     recurring: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    is_recurring: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     subscribe: Option<char>,
@@ -62,11 +77,17 @@ pub struct SubscribeRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     sender_last_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    sender_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sender_postal_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
-    #[serde(rename = "dae", skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
     info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -81,27 +102,28 @@ pub struct SubscribeRequest {
 
 impl SubscribeRequest {
     /// Constructs a new subscription request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
+        value: impl Into<MonetaryValue>,
         card: String,
         exp_month: String,
         exp_year: String,
-        currency: Currency,
         order_id: String,
         description: String,
         subscribe_date_start: String,
         period: SubscribePeriodicity,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::Subscribe,
             public_key: public_key.into(),
-            amount,
-            card,
+            amount: value.amount(),
+            card: card.into(),
             card_exp_month: exp_month,
             card_exp_year: exp_year,
-            currency,
+            currency: value.currency(),
             order_id,
             description,
             subscribe_date_start,
@@ -113,10 +135,12 @@ impl SubscribeRequest {
             prepare: None,
             recurring_by_token: None,
             recurring: None,
+            is_recurring: None,
             server_url: None,
             subscribe: None,
             sender_first_name: None,
             sender_last_name: None,
+            sender_email: None,
             sender_country_code: None,
             sender_city: None,
             sender_address: None,
@@ -131,9 +155,36 @@ impl SubscribeRequest {
         }
     }
 
+    /// Constructs a new subscription request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        card: String,
+        exp_month: String,
+        exp_year: String,
+        currency: Currency,
+        order_id: String,
+        description: String,
+        subscribe_date_start: String,
+        period: SubscribePeriodicity,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            exp_month,
+            exp_year,
+            order_id,
+            description,
+            subscribe_date_start,
+            period,
+        )
+    }
+
     /// Sets the card CVV/CVV2 code.
     pub fn cvv(mut self, cvv: String) -> Self {
-        self.card_cvv = Some(cvv);
+        self.card_cvv = Some(cvv.into());
         self
     }
 
@@ -158,7 +209,14 @@ impl SubscribeRequest {
     /// Sets the preliminary preparation of the payment. The mode allows to determine data completeness,
     /// whether 3DS is required or limit is exceeded. The funds are not debited.
     pub fn prepare(mut self) -> Self {
-        self.prepare = Some('1');
+        self.prepare = Some(Prepare::Enable);
+        self
+    }
+
+    /// Sets the preliminary preparation of the payment in the tariffs mode, which returns
+    /// the commissions of the payment. The funds are not debited.
+    pub fn prepare_tariffs(mut self) -> Self {
+        self.prepare = Some(Prepare::Tariffs);
         self
     }
 
@@ -180,6 +238,19 @@ impl SubscribeRequest {
         self
     }
 
+    /// Sets the first payment of the subscription as initiated by the merchant (MIT).
+    /// The payments scheduled by the subscription are always merchant-initiated.
+    pub fn merchant_initiated(mut self) -> Self {
+        self.is_recurring = Some(true);
+        self
+    }
+
+    /// Sets the first payment of the subscription as initiated by the customer (CIT).
+    pub fn customer_initiated(mut self) -> Self {
+        self.is_recurring = Some(false);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -205,7 +276,7 @@ impl SubscribeRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -223,6 +294,12 @@ impl SubscribeRequest {
         self
     }
 
+    /// Sets the sender's email.
+    pub fn sender_email(mut self, email: String) -> Self {
+        self.sender_email = Some(email);
+        self
+    }
+
     /// Sets the sender's postal code.
     pub fn sender_postal_code(mut self, code: String) -> Self {
         self.sender_postal_code = Some(code);
@@ -237,7 +314,7 @@ impl SubscribeRequest {
 
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -247,6 +324,12 @@ impl SubscribeRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
     /// Sets the product category. Must not exceed 25 symbols.
     pub fn product_category(mut self, category: String) -> Self {
         self.product_category = Some(category);
@@ -270,118 +353,45 @@ impl SubscribeRequest {
         self.product_url = Some(url);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
-impl LiqPayRequest<SubscribeResponse, Sha3_256> for SubscribeRequest {}
+impl LiqPayRequest<SubscribeResponse, Sha3_256> for SubscribeRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 /// Represents the response to a subscription operation.
-#[derive(Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SubscribeResponse {
-    /// Represents the result of the request. Can be either `ok` or `error`.
-    pub result: Result,
-    /// Represents the status of the request. Possible values are `error` - incorrect data,
-    /// `failure` - payment failed, `subscribed` - successful subscription, `reversed` - payment refunded,
-    /// `success` - successful payment, `3ds_verify` - the verification by 3DS is required,
-    /// `cvv_verify` - CVV required, `otp_verify` - confirmation by OTP is required,
-    /// `receiver_verify` - additional receiver information is required,
-    /// `sender_verify` - additional sender information is required,
-    /// `wait_accept` - the store is not verified at this point, but the money are withdrawn from a client,
-    /// `wait_secure` - verified payment.
-    pub status: Status,
-    /// Represents the identifier of an acquirer.
-    #[serde(rename = "acq_id")]
-    pub acquirer_id: Option<u32>,
-    /// Represents the operation type. Possible values are `pay` - payment,
-    /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation,
-    /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
-    pub action: Option<Action>,
-    /// Represents the agent commission.
-    pub agent_commission: Option<f64>,
-    /// Represents the payment amount.
-    pub amount: Option<f64>,
-    /// Represents the authorization code for debit.
-    pub authcode_debit: Option<String>,
-    /// Represents the payer bonus amount.
-    pub amount_bonus: Option<f64>,
-    /// Represents the payment credit amount.
-    pub amount_credit: Option<f64>,
-    /// Represents the payment debit amount.
-    pub amount_debit: Option<f64>,
-    /// Represents the sender's card token.
-    pub card_token: Option<String>,
-    /// Represents the commission charged to credit.
-    pub commission_credit: Option<f64>,
-    /// Represents the commission charged to debit.
-    pub commission_debit: Option<f64>,
-    /// Represents the payment creation date.
-    #[serde(rename = "create_date")]
-    pub creation_date: Option<u64>,
-    /// Represents the currency of the payment.
-    pub currency: Option<String>,
-    /// Represents the currency used for credit.
-    pub currency_credit: Option<Currency>,
-    /// Represents the currency used for debit.
-    pub currency_debit: Option<Currency>,
-    /// Represents the payment description.
-    pub description: Option<String>,
-    /// Represents the end date of the payment.
-    pub end_date: Option<u64>,
-    /// Indicates whether a transaction passed with 3DS.
-    pub is_3ds: Option<bool>,
-    /// Represents the Id of an order in the LiqPay system.
-    pub liqpay_order_id: Option<String>,
-    /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
-    /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
-    pub mpi_eci: Option<MpiEci>,
-    /// Represents the identifier of an order.
-    pub order_id: Option<String>,
-    /// Represents the identifier of a payment.
-    pub payment_id: Option<u64>,
-    /// Represents the payment type. Possible values are `card` - paid by card,
-    /// `privat24` - account in the Privat24 system, `moment_part` - installment,
-    /// `cash` - paid in cash, `invoice` - invoiced to an email,
-    /// `qr` - paid by scanning a QR code.
-    #[serde(rename = "paytype")]
-    pub pay_type: Option<PayType>,
-    /// Represents the public key of the shop.
-    pub public_key: Option<String>,
-    /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for debit.
-    #[serde(rename = "rrn_debit")]
-    pub retrieval_reference_number_debit: Option<String>,
-    /// Represents the sender's bonus amount.
-    pub sender_bonus: Option<f64>,
-    /// Represents the sender's bank.
-    pub sender_card_bank: Option<String>,
-    /// Represents the sender's card country code in the ISO-3166 format.
-    pub sender_card_country: Option<u16>,
-    /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
-    pub sender_card_mask: Option<String>,
-    /// Represents the sender's card type - either VISA or MasterCard.
-    pub sender_card_type: Option<String>,
-    /// Represents the sender's commission amount.
-    pub sender_commission: Option<f64>,
-    /// Represents the sender's first name.
-    pub sender_first_name: Option<String>,
-    /// Represents the sender's last name.
-    pub sender_last_name: Option<String>,
-    /// Represents the sender's phone number.
-    pub sender_phone: Option<String>,
-    /// Represents the identifier of a transaction in LiqPay.
-    pub transaction_id: Option<u64>,
-    /// Represents the type of an operation.
-    #[serde(rename = "type")]
-    pub operation_type: Option<String>,
-    /// Represents the API version.
-    pub version: Option<Version>,
-    /// Holds an error code.
-    #[serde(rename = "err_code")]
-    pub error_code: Option<String>,
-    /// Holds an error description.
-    #[serde(rename = "err_description")]
-    pub error_description: Option<String>,
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
 }
 
 impl LiqPayResponse for SubscribeResponse {}
@@ -389,28 +399,41 @@ impl LiqPayResponse for SubscribeResponse {}
 /// Represents a request to cancel a subscription.
 #[derive(Debug, Serialize)]
 pub struct CancelSubscriptionRequest {
-    version: Version,
     action: Action,
     public_key: String,
     order_id: String,
 }
 
-impl LiqPayRequest<CancelSubscriptionResponse, Sha3_256> for CancelSubscriptionRequest {}
+impl LiqPayRequest<CancelSubscriptionResponse, Sha3_256> for CancelSubscriptionRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl CancelSubscriptionRequest {
     /// Constructs a new subscription cancellation request.
     pub fn new(public_key: impl Into<String>, order_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Unsubscribe,
             public_key: public_key.into(),
             order_id,
         }
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a subscription cancellation operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CancelSubscriptionResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -426,20 +449,27 @@ pub struct CancelSubscriptionResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -455,6 +485,7 @@ pub struct CancelSubscriptionResponse {
     /// Represents the end date of the payment.
     pub end_date: Option<u64>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -474,19 +505,22 @@ pub struct CancelSubscriptionResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's phone number.
     pub sender_phone: Option<String>,
@@ -510,40 +544,74 @@ impl LiqPayResponse for CancelSubscriptionResponse {}
 /// Represents a request to update a subscription.
 #[derive(Debug, Serialize)]
 pub struct UpdateSubscriptionRequest {
-    version: Version,
     action: Action,
     public_key: String,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
     description: String,
 }
 
-impl LiqPayRequest<UpdateSubscriptionResponse, Sha3_256> for UpdateSubscriptionRequest {}
+impl LiqPayRequest<UpdateSubscriptionResponse, Sha3_256> for UpdateSubscriptionRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl UpdateSubscriptionRequest {
     /// Constructs a new subscription update request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::UpdateSubscription,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
         }
     }
+
+    /// Constructs a new subscription update request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            order_id,
+            description,
+        )
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a subscription update operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct UpdateSubscriptionResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -559,20 +627,27 @@ pub struct UpdateSubscriptionResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -588,6 +663,7 @@ pub struct UpdateSubscriptionResponse {
     /// Represents the end date of the payment.
     pub end_date: Option<u64>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -607,19 +683,22 @@ pub struct UpdateSubscriptionResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's phone number.
     pub sender_phone: Option<String>,
@@ -639,3 +718,83 @@ pub struct UpdateSubscriptionResponse {
 }
 
 impl LiqPayResponse for UpdateSubscriptionResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{SubscribePeriodicity, SubscribeRequest, UpdateSubscriptionRequest};
+    use crate::common::enums::Currency;
+    use crate::error::LiqPayError;
+
+    fn request() -> SubscribeRequest {
+        SubscribeRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+            "2030-01-01 00:00:00".to_owned(),
+            SubscribePeriodicity::Month,
+        )
+    }
+
+    fn serialize(request: SubscribeRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn recurring_flags_are_sent_under_their_own_keys() {
+        let merchant_initiated = serialize(request().merchant_initiated());
+        let customer_initiated = serialize(request().customer_initiated());
+        let recurring = serialize(request().recurring());
+        let unset = serialize(request());
+
+        assert_eq!(merchant_initiated["is_recurring"], true);
+        assert!(merchant_initiated.get("recurring").is_none());
+        assert_eq!(customer_initiated["is_recurring"], false);
+        assert_eq!(recurring["recurring"], true);
+        assert!(recurring.get("is_recurring").is_none());
+        assert!(unset.get("is_recurring").is_none());
+    }
+
+    fn update(amount: f64) -> UpdateSubscriptionRequest {
+        UpdateSubscriptionRequest::with_value(
+            "public_key",
+            (amount, Currency::UAH),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    #[test]
+    fn non_positive_update_amount_is_rejected() {
+        for amount in [0.0, -10.0, 0.004] {
+            assert!(
+                matches!(update(amount).build(), Err(LiqPayError::Validation(_))),
+                "{amount}"
+            );
+        }
+    }
+
+    #[test]
+    fn positive_update_amount_is_accepted_and_sent_rounded() {
+        let request = update(10.505).build().unwrap();
+
+        assert_eq!(serde_json::to_value(request).unwrap()["amount"], 10.51);
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = request().sender_email("taras@example.com".to_owned());
+        let invalid = request().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serialize(valid.build().unwrap())["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+}
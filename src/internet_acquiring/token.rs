@@ -1,20 +1,30 @@
+use std::result::Result as StdResult;
+
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha1::Sha1;
 
-use crate::common::enums::{
-    Action, Bonus, Currency, Language, MpiEci, PayType, Result, Status, Version,
-};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::enums::{Action, Currency, Language, Prepare};
+use crate::common::responses::PaymentResponse;
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, PRODUCT_CATEGORY_MAX_LENGTH, check_customer, check_email,
+    check_max_length, check_order_id, check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::DetailAddenda;
 
-/// Represents a request to perform a token-based payment.
+/// Represents a request to perform a token-based payment. A token payment may still require
+/// a verification, e.g. by 3DS or an OTP, which is reported by the response status and continued
+/// with the verification requests.
 #[derive(Serialize, Debug)]
 pub struct TokenPaymentRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     card_token: String,
     currency: Currency,
     order_id: String,
@@ -26,7 +36,7 @@ pub struct TokenPaymentRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    prepare: Option<char>,
+    prepare: Option<Prepare>,
     #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,11 +54,17 @@ pub struct TokenPaymentRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     sender_last_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    sender_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sender_postal_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
-    #[serde(rename = "dae", skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
     info: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -63,25 +79,40 @@ pub struct TokenPaymentRequest {
     is_recurring: Option<bool>,
 }
 
-impl LiqPayRequest<TokenPaymentResponse, Sha1> for TokenPaymentRequest {}
+impl LiqPayRequest<TokenPaymentResponse, Sha1> for TokenPaymentRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl TokenPaymentRequest {
     /// Constructs a new token payment request.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
+        value: impl Into<MonetaryValue>,
         card_token: String,
-        currency: Currency,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Three,
             action: Action::Pay,
             public_key: public_key.into(),
-            amount,
+            amount: value.amount(),
             card_token,
-            currency,
+            currency: value.currency(),
             order_id,
             description,
             ip: None,
@@ -93,6 +124,7 @@ impl TokenPaymentRequest {
             split_tickets_only: None,
             sender_first_name: None,
             sender_last_name: None,
+            sender_email: None,
             sender_country_code: None,
             sender_city: None,
             sender_address: None,
@@ -108,6 +140,25 @@ impl TokenPaymentRequest {
         }
     }
 
+    /// Constructs a new token payment request.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        card_token: String,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card_token,
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the customer's IP address.
     pub fn ip(mut self, ip: String) -> Self {
         self.ip = Some(ip);
@@ -130,7 +181,14 @@ impl TokenPaymentRequest {
     /// Sets the preliminary preparation of the payment. The mode allows to determine data completeness,
     /// whether 3DS is required or limit is exceeded. The funds are not debited.
     pub fn prepare(mut self) -> Self {
-        self.prepare = Some('1');
+        self.prepare = Some(Prepare::Enable);
+        self
+    }
+
+    /// Sets the preliminary preparation of the payment in the tariffs mode, which returns
+    /// the commissions of the payment. The funds are not debited.
+    pub fn prepare_tariffs(mut self) -> Self {
+        self.prepare = Some(Prepare::Tariffs);
         self
     }
 
@@ -166,7 +224,7 @@ impl TokenPaymentRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -184,6 +242,12 @@ impl TokenPaymentRequest {
         self
     }
 
+    /// Sets the sender's email.
+    pub fn sender_email(mut self, email: String) -> Self {
+        self.sender_email = Some(email);
+        self
+    }
+
     /// Sets the sender's postal code.
     pub fn sender_postal_code(mut self, code: String) -> Self {
         self.sender_postal_code = Some(code);
@@ -198,7 +262,7 @@ impl TokenPaymentRequest {
 
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -208,6 +272,12 @@ impl TokenPaymentRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
     /// Sets the product category. Must not exceed 25 symbols.
     pub fn product_category(mut self, category: String) -> Self {
         self.product_category = Some(category);
@@ -232,139 +302,115 @@ impl TokenPaymentRequest {
         self
     }
 
-    /// Sets the payment as initiated by the merchant.
+    /// Sets the payment as initiated by the merchant (MIT), i.e. performed without a client.
+    /// Unlike a subscription, LiqPay does not schedule further payments.
     pub fn merchant_initiated(mut self) -> Self {
         self.is_recurring = Some(true);
         self
     }
 
-    /// Sets the payment as initiated by the customer.
+    /// Sets the payment as initiated by the customer (CIT), i.e. performed with a client.
     pub fn customer_initiated(mut self) -> Self {
         self.is_recurring = Some(false);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        if let Some(category) = &self.product_category {
+            check_max_length("product_category", category, PRODUCT_CATEGORY_MAX_LENGTH)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents the response to a token payment operation.
-#[derive(Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct TokenPaymentResponse {
-    /// Represents the result of the request. Can be either `ok` or `error`.
-    pub result: Result,
-    /// Represents the status of the request. Possible values are `error` - incorrect data,
-    /// `failure` - payment failed, `reversed` - payment refunded, `success` - successful payment,
-    /// `wait_accept` - the store is not verified at this point, but the money are withdrawn from a client,
-    /// `wait_secure` - verified payment.
-    pub status: Status,
-    /// Represents the identifier of an acquirer.
-    #[serde(rename = "acq_id")]
-    pub acquirer_id: Option<u32>,
-    /// Represents the operation type. Possible values are `pay` - payment,
-    /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation,
-    /// `auth` - card preauthentication, `regular` - regular payment.
-    pub action: Option<Action>,
-    /// Represents the agent commission.
-    pub agent_commission: Option<f64>,
-    /// Represents the payment amount.
-    pub amount: Option<f64>,
-    /// Represents the payer bonus amount.
-    pub amount_bonus: Option<f64>,
-    /// Represents the payment credit amount.
-    pub amount_credit: Option<f64>,
-    /// Represents the payment debit amount.
-    pub amount_debit: Option<f64>,
-    /// Represents the authorization code for credit.
-    pub authcode_credit: Option<String>,
-    /// Represents the authorization code for debit.
-    pub authcode_debit: Option<String>,
-    /// Represents the bonus percentage.
-    #[serde(rename = "bonus_procent")]
-    pub bonus_percent: Option<f32>,
-    /// Represents the bonus type. Possible values are `bonusplus`, `personal`, `promo` and `discount_club`.
-    pub bonus_type: Option<Bonus>,
-    /// Represents the sender's card token.
-    pub card_token: Option<String>,
-    /// Represents the commission charged to credit.
-    pub commission_credit: Option<f64>,
-    /// Represents the commission charged to debit.
-    pub commission_debit: Option<f64>,
-    /// Represents the phone number used for confirmation via a one-time password.
-    pub confirm_phone: Option<String>,
-    /// Represents the payment creation date.
-    #[serde(rename = "create_date")]
-    pub creation_date: Option<u64>,
-    /// Represents the currency of the payment.
-    pub currency: Option<String>,
-    /// Represents the currency used for credit.
-    pub currency_credit: Option<Currency>,
-    /// Represents the currency used for debit.
-    pub currency_debit: Option<Currency>,
-    /// Represents the payment description.
-    pub description: Option<String>,
-    /// Represents the end date of the payment.
-    pub end_date: Option<u64>,
-    /// Represents the IP address of a sender.
-    pub ip: Option<String>,
-    /// Indicates whether a transaction passed with 3DS.
-    pub is_3ds: Option<bool>,
-    /// Represents the language of the payment.
-    pub language: Option<Language>,
-    /// Represents the Id of an order in the LiqPay system.
-    pub liqpay_order_id: Option<String>,
-    /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
-    /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
-    pub mpi_eci: Option<MpiEci>,
-    /// Represents the identifier of an order.
-    pub order_id: Option<String>,
-    /// Represents the identifier of a payment.
-    pub payment_id: Option<u64>,
-    /// Represents the payment type. Possible values are `card` - paid by card,
-    /// `privat24` - account in the Privat24 system, `moment_part` - installment,
-    /// `cash` - paid in cash, `invoice` - invoiced to an email,
-    /// `qr` - paid by scanning a QR code.
-    #[serde(rename = "paytype")]
-    pub pay_type: Option<PayType>,
-    /// Represents the public key of the shop.
-    pub public_key: Option<String>,
-    /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for credit.
-    #[serde(rename = "rrn_credit")]
-    pub retrieval_reference_number_credit: Option<String>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for debit.
-    #[serde(rename = "rrn_debit")]
-    pub retrieval_reference_number_debit: Option<String>,
-    /// Represents the sender's bonus amount.
-    pub sender_bonus: Option<f64>,
-    /// Represents the sender's bank.
-    pub sender_card_bank: Option<String>,
-    /// Represents the sender's card country code in the ISO-3166 format.
-    pub sender_card_country: Option<u16>,
-    /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
-    pub sender_card_mask: Option<String>,
-    /// Represents the sender's card type - either VISA or MasterCard.
-    pub sender_card_type: Option<String>,
-    /// Represents the sender's commission amount.
-    pub sender_commission: Option<f64>,
-    /// Represents the sender's first name.
-    pub sender_first_name: Option<String>,
-    /// Represents the sender's last name.
-    pub sender_last_name: Option<String>,
-    /// Represents the sender's phone number.
-    pub sender_phone: Option<String>,
-    /// Represents the identifier of a transaction in LiqPay.
-    pub transaction_id: Option<u64>,
-    /// Represents the type of an operation.
-    #[serde(rename = "type")]
-    pub operation_type: Option<String>,
-    /// Represents the API version.
-    pub version: Option<Version>,
-    /// Holds an error code.
-    #[serde(rename = "err_code")]
-    pub error_code: Option<String>,
-    /// Holds an error description.
-    #[serde(rename = "err_description")]
-    pub error_description: Option<String>,
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
 }
 
 impl LiqPayResponse for TokenPaymentResponse {}
+
+impl TokenPaymentResponse {
+    /// Indicates whether the payment must be verified, e.g. by 3DS, an OTP or a CVV, before it is completed.
+    /// Delegates to `Status::requires_action`, so any status awaiting the customer is covered.
+    pub fn requires_verification(&self) -> bool {
+        self.payment.status.requires_action()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{TokenPaymentRequest, TokenPaymentResponse};
+    use crate::common::enums::Currency;
+    use crate::common::validation::PRODUCT_CATEGORY_MAX_LENGTH;
+    use crate::error::LiqPayError;
+
+    fn categorized(category: &str) -> TokenPaymentRequest {
+        TokenPaymentRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "card_token".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+        .product_category(category.to_owned())
+    }
+
+    fn response(status: &str) -> TokenPaymentResponse {
+        serde_json::from_value(json!({ "result": "ok", "status": status })).unwrap()
+    }
+
+    #[test]
+    fn product_category_over_limit_is_rejected() {
+        let category = "c".repeat(PRODUCT_CATEGORY_MAX_LENGTH + 1);
+
+        assert!(matches!(
+            categorized(&category).build(),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn product_category_within_limit_is_accepted() {
+        let category = "c".repeat(PRODUCT_CATEGORY_MAX_LENGTH);
+
+        assert!(categorized(&category).build().is_ok());
+    }
+
+    #[test]
+    fn verify_status_requires_verification() {
+        assert!(response("3ds_verify").requires_verification());
+        assert!(response("otp_verify").requires_verification());
+        assert!(!response("success").requires_verification());
+        assert!(!response("failure").requires_verification());
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = categorized("category").sender_email("taras@example.com".to_owned());
+        let invalid = categorized("category").sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serde_json::to_value(valid.build().unwrap()).unwrap()["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+}
@@ -1,10 +1,22 @@
+use std::result::Result as StdResult;
+
 use base64::{Engine, engine::general_purpose};
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Prepare};
+use crate::common::responses::PaymentResponse;
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_email, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 use crate::internet_acquiring::common::{DetailAddenda, ElectronicCommerceIndicator, RroInfo};
 
 /// Represents a digital wallet option.
@@ -17,116 +29,177 @@ pub enum DigitalWallet {
 /// Represents a request to block funds.
 #[derive(Serialize, Debug)]
 pub struct FundsBlockingRequest {
-    version: Version,
-    public_key: String,
-    action: Action,
-    amount: f64,
-    currency: Currency,
-    order_id: String,
-    description: String,
+    pub(super) public_key: String,
+    pub(super) action: Action,
+    pub(super) amount: Amount,
+    pub(super) currency: Currency,
+    pub(super) order_id: String,
+    pub(super) description: String,
     #[serde(rename = "applepay_token", skip_serializing_if = "Option::is_none")]
-    apple_pay_token: Option<String>,
+    pub(super) apple_pay_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card: Option<String>,
+    pub(super) card: Option<CardNumber>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    pub(super) card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_exp_month: Option<String>,
+    pub(super) card_exp_month: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_exp_year: Option<String>,
+    pub(super) card_exp_year: Option<String>,
     #[serde(rename = "gpay_token", skip_serializing_if = "Option::is_none")]
-    google_pay_token: Option<String>,
+    pub(super) google_pay_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    ip: Option<String>,
+    pub(super) ip: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    phone: Option<String>,
+    pub(super) phone: Option<String>,
     #[serde(rename = "paytype", skip_serializing_if = "Option::is_none")]
-    pay_type: Option<PayType>,
+    pub(super) pay_type: Option<PayType>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tid: Option<String>,
+    pub(super) tid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    language: Option<Language>,
+    pub(super) language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    prepare: Option<char>,
+    pub(super) prepare: Option<Prepare>,
     #[serde(rename = "recurringbytoken", skip_serializing_if = "Option::is_none")]
-    recurring_by_token: Option<char>,
+    pub(super) recurring_by_token: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) recurring: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    recurring: Option<bool>,
+    pub(super) is_recurring: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    server_url: Option<String>,
+    pub(super) server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    tavv: Option<String>,
+    pub(super) tavv: Option<String>,
     #[serde(rename = "eci", skip_serializing_if = "Option::is_none")]
-    electronic_commerce_indicator: Option<ElectronicCommerceIndicator>,
+    pub(super) electronic_commerce_indicator: Option<ElectronicCommerceIndicator>,
     #[serde(rename = "cavv", skip_serializing_if = "Option::is_none")]
-    cardholder_authentication_verification_value: Option<String>,
+    pub(super) cardholder_authentication_verification_value: Option<String>,
     #[serde(rename = "tdsv", skip_serializing_if = "Option::is_none")]
-    three_ds_version: Option<String>,
+    pub(super) three_ds_version: Option<String>,
     #[serde(rename = "dsTransID", skip_serializing_if = "Option::is_none")]
-    three_ds_transaction_id: Option<String>,
+    pub(super) three_ds_transaction_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_first_name: Option<String>,
+    pub(super) mpi_eci: Option<MpiEci>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_last_name: Option<String>,
+    pub(super) sender_first_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_email: Option<String>,
+    pub(super) sender_last_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_country_code: Option<String>,
+    pub(super) sender_email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_city: Option<String>,
+    pub(super) sender_country_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_address: Option<String>,
+    pub(super) sender_city: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_state: Option<String>,
+    pub(super) sender_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_shipping_state: Option<String>,
+    pub(super) sender_state: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    sender_postal_code: Option<String>,
+    pub(super) sender_shipping_state: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    split_rules: Option<String>,
+    pub(super) sender_postal_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    customer: Option<String>,
+    pub(super) split_rules: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    detail_addenda: Option<String>,
+    pub(super) customer: Option<String>,
+    #[serde(
+        rename = "dae",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "DetailAddenda::serialize_base64"
+    )]
+    pub(super) detail_addenda: Option<DetailAddenda>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    info: Option<String>,
+    pub(super) info: Option<String>,
 }
 
-impl LiqPayRequest<FundsBlockingResponse, Sha3_256> for FundsBlockingRequest {}
+/// Encodes a digital wallet token to base64, unless it is a base64-encoded JSON token already.
+fn encode_wallet_token(token: String) -> String {
+    let is_encoded = general_purpose::STANDARD
+        .decode(token.trim())
+        .ok()
+        .and_then(|decoded| serde_json::from_slice::<Value>(&decoded).ok())
+        .is_some_and(|decoded| decoded.is_object());
+
+    if is_encoded {
+        token.trim().to_owned()
+    } else {
+        general_purpose::STANDARD.encode(token)
+    }
+}
+
+impl LiqPayRequest<FundsBlockingResponse, Sha3_256> for FundsBlockingRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl FundsBlockingRequest {
     /// Constructs a new funds blocking request by a payment card.
-    pub fn card(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn card_with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         card: String,
         exp_month: String,
         exp_year: String,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
+        let mut request = Self::new(public_key, value.into(), order_id, description);
 
-        request.card = Some(card);
+        request.card = Some(card.into());
         request.card_exp_month = Some(exp_month);
         request.card_exp_year = Some(exp_year);
 
         request
     }
 
-    /// Constructs a new funds blocking request by a digital wallet.
-    pub fn digital_wallet(
+    /// Constructs a new funds blocking request by a payment card.
+    #[deprecated(note = "use `card_with_value`, which pairs the amount with its currency")]
+    pub fn card(
         public_key: impl Into<String>,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: Currency,
+        card: String,
+        exp_month: String,
+        exp_year: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::card_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            exp_month,
+            exp_year,
+            order_id,
+            description,
+        )
+    }
+
+    /// Constructs a new funds blocking request by a digital wallet. The token is either the JSON
+    /// payment token received from Apple Pay or Google Pay, which is encoded to base64, or the same token
+    /// already encoded to base64, which is sent as is.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn digital_wallet_with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
         token: impl Into<String>,
         digital_wallet: DigitalWallet,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
-        let base64_token = general_purpose::STANDARD.encode(token.into());
+        let mut request = Self::new(public_key, value.into(), order_id, description);
+        let base64_token = encode_wallet_token(token.into());
 
         match digital_wallet {
             DigitalWallet::ApplePay => {
@@ -142,6 +215,31 @@ impl FundsBlockingRequest {
         request
     }
 
+    /// Constructs a new funds blocking request by a digital wallet. The token is either the JSON
+    /// payment token received from Apple Pay or Google Pay, which is encoded to base64, or the same token
+    /// already encoded to base64, which is sent as is.
+    #[deprecated(
+        note = "use `digital_wallet_with_value`, which pairs the amount with its currency"
+    )]
+    pub fn digital_wallet(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        token: impl Into<String>,
+        digital_wallet: DigitalWallet,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::digital_wallet_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            token,
+            digital_wallet,
+            order_id,
+            description,
+        )
+    }
+
     /// Sets a decrypted token from an apple device.
     pub fn tavv(mut self, tavv: impl Into<String>) -> Self {
         self.pay_type = Some(PayType::Tavv);
@@ -168,7 +266,7 @@ impl FundsBlockingRequest {
 
     /// Sets the card CVV/CVV2 code.
     pub fn cvv(mut self, cvv: String) -> Self {
-        self.card_cvv = Some(cvv);
+        self.card_cvv = Some(cvv.into());
         self
     }
 
@@ -199,7 +297,14 @@ impl FundsBlockingRequest {
     /// Sets the preliminary preparation of the payment. The mode allows to determine data completeness,
     /// whether 3DS is required or limit is exceeded. The funds are not debited.
     pub fn prepare(mut self) -> Self {
-        self.prepare = Some('1');
+        self.prepare = Some(Prepare::Enable);
+        self
+    }
+
+    /// Sets the preliminary preparation of the payment in the tariffs mode, which returns
+    /// the commissions of the payment. The funds are not debited.
+    pub fn prepare_tariffs(mut self) -> Self {
+        self.prepare = Some(Prepare::Tariffs);
         self
     }
 
@@ -215,6 +320,24 @@ impl FundsBlockingRequest {
         self
     }
 
+    /// Sets the operation to be performed with a client.
+    pub fn not_recurring(mut self) -> Self {
+        self.recurring = Some(false);
+        self
+    }
+
+    /// Sets the funds blocking as initiated by the merchant (MIT), i.e. performed without a client.
+    pub fn merchant_initiated(mut self) -> Self {
+        self.is_recurring = Some(true);
+        self
+    }
+
+    /// Sets the funds blocking as initiated by the customer (CIT), i.e. performed with a client.
+    pub fn customer_initiated(mut self) -> Self {
+        self.is_recurring = Some(false);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -247,6 +370,12 @@ impl FundsBlockingRequest {
         self
     }
 
+    /// Sets the MPI ECI code of a 3DS verification performed on the merchant's side.
+    pub fn mpi_eci(mut self, eci: MpiEci) -> Self {
+        self.mpi_eci = Some(eci);
+        self
+    }
+
     /// Sets the sender's first name.
     pub fn sender_first_name(mut self, name: String) -> Self {
         self.sender_first_name = Some(name);
@@ -265,7 +394,7 @@ impl FundsBlockingRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -316,7 +445,7 @@ impl FundsBlockingRequest {
 
     /// Sets the transportation details.
     pub fn detail_addenda(mut self, detail: DetailAddenda) -> Self {
-        self.detail_addenda = Some(detail.to_base64());
+        self.detail_addenda = Some(detail);
         self
     }
 
@@ -326,19 +455,38 @@ impl FundsBlockingRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
+
     fn new(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: MonetaryValue,
         order_id: String,
         description: String,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Hold,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
             card: None,
@@ -351,6 +499,7 @@ impl FundsBlockingRequest {
             cardholder_authentication_verification_value: None,
             three_ds_version: None,
             three_ds_transaction_id: None,
+            mpi_eci: None,
             card_cvv: None,
             ip: None,
             phone: None,
@@ -360,6 +509,7 @@ impl FundsBlockingRequest {
             prepare: None,
             recurring_by_token: None,
             recurring: None,
+            is_recurring: None,
             server_url: None,
             split_rules: None,
             sender_first_name: None,
@@ -379,119 +529,11 @@ impl FundsBlockingRequest {
 }
 
 /// Represents a response to a funds blocking operation.
-#[derive(Deserialize, Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct FundsBlockingResponse {
-    /// Represents the result of the request. Can be either `ok` or `error`.
-    pub result: Result,
-    /// Represents the status of the request. Possible values are `error` - incorrect data,
-    /// `failure` - payment failed, `reversed` - payment refunded, `success` - successful payment,
-    /// `3ds_verify` - the verification by 3DS is required, `cvv_verify` - CVV required,
-    /// `otp_verify` - confirmation by OTP is required, `receiver_verify` - additional receiver
-    /// information is required, `sender_verify` - additional sender information is required,
-    /// `hold_wait` - successful blocking of the amount on the sender's account,
-    /// `wait_accept` - the store is not verified at this point, but the money are withdrawn from a client,
-    /// `wait_secure` - verified payment.
-    pub status: Status,
-    /// Represents the identifier of an acquirer.
-    #[serde(rename = "acq_id")]
-    pub acquirer_id: Option<u32>,
-    /// Represents the operation type. Possible values are `pay` - payment,
-    /// `hold` - hold operation on a sender's account, `subscribe` - subscription, `paydonate` - donation,
-    /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
-    pub action: Option<Action>,
-    /// Represents the agent commission.
-    pub agent_commission: Option<f64>,
-    /// Represents the payment amount.
-    pub amount: Option<f64>,
-    /// Represents the payer bonus amount.
-    pub amount_bonus: Option<f64>,
-    /// Represents the payment credit amount.
-    pub amount_credit: Option<f64>,
-    /// Represents the payment debit amount.
-    pub amount_debit: Option<f64>,
-    /// Represents the authorization code for debit.
-    pub authcode_debit: Option<String>,
-    /// Represents the sender's card token.
-    pub card_token: Option<String>,
-    /// Represents the commission charged to credit.
-    pub commission_credit: Option<f64>,
-    /// Represents the commission charged to debit.
-    pub commission_debit: Option<f64>,
-    /// Represents the phone number used for confirmation via a one-time password.
-    pub confirm_phone: Option<String>,
-    /// Represents the payment creation date.
-    #[serde(rename = "create_date")]
-    pub creation_date: Option<u64>,
-    /// Represents the currency of the payment.
-    pub currency: Option<String>,
-    /// Represents the currency used for credit.
-    pub currency_credit: Option<Currency>,
-    /// Represents the currency used for debit.
-    pub currency_debit: Option<Currency>,
-    /// Represents the payment description.
-    pub description: Option<String>,
-    /// Represents the IP address of a sender.
-    pub ip: Option<String>,
-    /// Represents the end date of the payment.
-    pub end_date: Option<u64>,
-    /// Indicates whether a transaction passed with 3DS.
-    pub is_3ds: Option<bool>,
-    /// Represents the language of the payment.
-    pub language: Option<Language>,
-    /// Represents the Id of an order in the LiqPay system.
-    pub liqpay_order_id: Option<String>,
-    /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
-    /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
-    pub mpi_eci: Option<MpiEci>,
-    /// Represents the identifier of an order.
-    pub order_id: Option<String>,
-    /// Represents the identifier of a payment.
-    pub payment_id: Option<u64>,
-    /// Represents the payment type. Possible values are `card` - paid by card,
-    /// `privat24` - account in the Privat24 system, `moment_part` - installment,
-    /// `cash` - paid in cash, `invoice` - invoiced to an email,
-    /// `qr` - paid by scanning a QR code.
-    #[serde(rename = "paytype")]
-    pub pay_type: Option<PayType>,
-    /// Represents the public key of the shop.
-    pub public_key: Option<String>,
-    /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for debit.
-    #[serde(rename = "rrn_debit")]
-    pub retrieval_reference_number_debit: Option<String>,
-    /// Represents the sender's bonus amount.
-    pub sender_bonus: Option<f64>,
-    /// Represents the sender's bank.
-    pub sender_card_bank: Option<String>,
-    /// Represents the sender's card country code in the ISO-3166 format.
-    pub sender_card_country: Option<u16>,
-    /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
-    pub sender_card_mask: Option<String>,
-    /// Represents the sender's card type - either VISA or MasterCard.
-    pub sender_card_type: Option<String>,
-    /// Represents the sender's commission amount.
-    pub sender_commission: Option<f64>,
-    /// Represents the sender's first name.
-    pub sender_first_name: Option<String>,
-    /// Represents the sender's last name.
-    pub sender_last_name: Option<String>,
-    /// Represents the sender's phone number.
-    pub sender_phone: Option<String>,
-    /// Represents the identifier of a transaction in LiqPay.
-    pub transaction_id: Option<u64>,
-    /// Represents the type of an operation.
-    #[serde(rename = "type")]
-    pub operation_type: Option<String>,
-    /// Represents the API version.
-    pub version: Option<Version>,
-    /// Holds an error code.
-    #[serde(rename = "err_code")]
-    pub error_code: Option<String>,
-    /// Holds an error description.
-    #[serde(rename = "err_description")]
-    pub error_description: Option<String>,
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
 }
 
 impl LiqPayResponse for FundsBlockingResponse {}
@@ -499,10 +541,9 @@ impl LiqPayResponse for FundsBlockingResponse {}
 /// Represents a request to complete a payment with blocked funds.
 #[derive(Debug, Serialize)]
 pub struct PaymentCompletionRequest {
-    version: Version,
     action: Action,
     public_key: String,
-    amount: f64,
+    amount: Amount,
     order_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     rro_info: Option<RroInfo>,
@@ -510,15 +551,24 @@ pub struct PaymentCompletionRequest {
     split_tickets_only: Option<bool>,
 }
 
-impl LiqPayRequest<PaymentCompletionResponse, Sha3_256> for PaymentCompletionRequest {}
+impl LiqPayRequest<PaymentCompletionResponse, Sha3_256> for PaymentCompletionRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+}
 
 impl PaymentCompletionRequest {
-    pub fn new(public_key: impl Into<String>, amount: f64, order_id: String) -> Self {
+    /// Constructs a new request to complete a payment with blocked funds. The amount
+    /// may be less than the blocked one, in which case the rest of the funds is released.
+    pub fn new(public_key: impl Into<String>, amount: impl Into<Amount>, order_id: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::HoldCompletion,
             public_key: public_key.into(),
-            amount,
+            amount: amount.into(),
             order_id,
             rro_info: None,
             split_tickets_only: None,
@@ -536,121 +586,179 @@ impl PaymentCompletionRequest {
         self.split_tickets_only = Some(true);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_positive_amount("amount", self.amount)?;
+
+        Ok(self)
+    }
+
+    /// Validates the request before it is sent against the amount blocked by the original
+    /// funds blocking operation. Fails if the completed amount exceeds the blocked one.
+    /// Both amounts are compared as they are sent to LiqPay, i.e. rounded to two decimal places.
+    pub fn build_for_hold(self, held_amount: impl Into<Amount>) -> StdResult<Self, LiqPayError> {
+        let amount = self.amount.serialized_value();
+        let held_amount = held_amount.into().serialized_value();
+
+        if amount > held_amount {
+            return Err(LiqPayError::Validation(format!(
+                "`amount` {} must not exceed the blocked amount {}",
+                amount, held_amount
+            )));
+        }
+
+        self.build()
+    }
 }
 
 /// Represents a response to a payment completion operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PaymentCompletionResponse {
-    /// Represents the result of the request. Can be either `ok` or `error`.
-    pub result: Result,
-    /// Represents the status of the request. Possible values are `error`, `failure`, `reversed`, `success`.
-    pub status: Status,
-    /// Represents the identifier of an acquirer.
-    #[serde(rename = "acq_id")]
-    pub acquirer_id: Option<u32>,
-    /// Represents the status of the request. Possible values are `error` - incorrect data,
-    /// `failure` - payment failed, `success` - successful payment.
-    pub action: Option<Action>,
-    /// Represents the agent commission.
-    pub agent_commission: Option<f64>,
-    /// Represents the payment amount.
-    pub amount: Option<f64>,
-    /// Represents the payer bonus amount.
-    pub amount_bonus: Option<f64>,
-    /// Represents the payment credit amount.
-    pub amount_credit: Option<f64>,
-    /// Represents the payment debit amount.
-    pub amount_debit: Option<f64>,
-    /// Represents the authorization code for credit.
-    pub authcode_credit: Option<String>,
-    /// Represents the authorization code for debit.
-    pub authcode_debit: Option<String>,
-    /// Represents the sender's card token.
-    pub card_token: Option<String>,
-    /// Represents the commission charged to credit.
-    pub commission_credit: Option<f64>,
-    /// Represents the commission charged to debit.
-    pub commission_debit: Option<f64>,
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
     /// Represents the date when functions were charged.
     pub completion_date: Option<String>,
-    /// Represents the phone number used for confirmation via a one-time password.
-    pub confirm_phone: Option<String>,
-    /// Represents the payment creation date.
-    #[serde(rename = "create_date")]
-    pub creation_date: Option<u64>,
-    /// Represents the currency of the payment.
-    pub currency: Option<String>,
-    /// Represents the currency used for credit.
-    pub currency_credit: Option<Currency>,
-    /// Represents the currency used for debit.
-    pub currency_debit: Option<Currency>,
-    /// Represents the payment description.
-    pub description: Option<String>,
-    /// Represents the IP address of a sender.
-    pub ip: Option<String>,
-    /// Represents the end date of the payment.
-    pub end_date: Option<u64>,
-    /// Indicates whether a transaction passed with 3DS.
-    pub is_3ds: Option<bool>,
-    /// Represents the language of the payment.
-    pub language: Option<Language>,
-    /// Represents the Id of an order in the LiqPay system.
-    pub liqpay_order_id: Option<String>,
-    /// Represents the MPI ECI code. Possible values are `5` - passed with 3DS,
-    /// `6` - 3DS is not supported by the card's issuer, `7` - passed without 3DS
-    pub mpi_eci: Option<MpiEci>,
-    /// Represents the identifier of an order.
-    pub order_id: Option<String>,
-    /// Represents the identifier of a payment.
-    pub payment_id: Option<u64>,
-    /// Represents the payment type. Possible values are `card` - paid by card,
-    /// `privat24` - account in the Privat24 system, `moment_part` - installment,
-    /// `cash` - paid in cash, `invoice` - invoiced to an email,
-    /// `qr` - paid by scanning a QR code.
-    #[serde(rename = "paytype")]
-    pub pay_type: Option<PayType>,
-    /// Represents the public key of the shop.
-    pub public_key: Option<String>,
-    /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for credit.
-    #[serde(rename = "rrn_credit")]
-    pub retrieval_reference_number_credit: Option<String>,
-    /// Represents the identifier of the transaction in a the issuer bank's system for debit.
-    #[serde(rename = "rrn_debit")]
-    pub retrieval_reference_number_debit: Option<String>,
-    /// Represents the sender's bonus amount.
-    pub sender_bonus: Option<f64>,
-    /// Represents the sender's bank.
-    pub sender_card_bank: Option<String>,
-    /// Represents the sender's card country code in the ISO-3166 format.
-    pub sender_card_country: Option<u16>,
-    /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
-    pub sender_card_mask: Option<String>,
-    /// Represents the sender's card type - either VISA or MasterCard.
-    pub sender_card_type: Option<String>,
-    /// Represents the sender's commission amount.
-    pub sender_commission: Option<f64>,
-    /// Represents the sender's first name.
-    pub sender_first_name: Option<String>,
-    /// Represents the sender's last name.
-    pub sender_last_name: Option<String>,
-    /// Represents the sender's phone number.
-    pub sender_phone: Option<String>,
-    /// Represents the identifier of a transaction in LiqPay.
-    pub transaction_id: Option<u64>,
-    /// Represents the type of an operation.
-    #[serde(rename = "type")]
-    pub operation_type: Option<String>,
-    /// Represents the API version.
-    pub version: Option<Version>,
-    /// Holds an error code.
-    #[serde(rename = "err_code")]
-    pub error_code: Option<String>,
-    /// Holds an error description.
-    #[serde(rename = "err_description")]
-    pub error_description: Option<String>,
 }
 
 impl LiqPayResponse for PaymentCompletionResponse {}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, engine::general_purpose};
+    use serde_json::Value;
+
+    use super::{DigitalWallet, FundsBlockingRequest, PaymentCompletionRequest};
+    use crate::common::enums::{Currency, MpiEci};
+    use crate::error::LiqPayError;
+    use crate::internet_acquiring::common::ElectronicCommerceIndicator;
+
+    fn funds_blocking() -> FundsBlockingRequest {
+        FundsBlockingRequest::card_with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    fn serialize(request: FundsBlockingRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    fn completion(amount: f64) -> PaymentCompletionRequest {
+        PaymentCompletionRequest::new("public_key", amount, "order".to_owned())
+    }
+
+    #[test]
+    fn completed_amount_is_compared_with_held_amount_after_rounding() {
+        assert!(completion(100.004).build_for_hold(100.0).is_ok());
+        assert!(completion(100.0).build_for_hold(99.995).is_ok());
+        assert!(completion(100.005).build_for_hold(100.0).is_err());
+        assert!(completion(100.01).build_for_hold(100.0).is_err());
+    }
+
+    #[test]
+    fn recurring_flags_are_sent_under_their_own_keys() {
+        let merchant_initiated = serialize(funds_blocking().merchant_initiated());
+        let customer_initiated = serialize(funds_blocking().customer_initiated());
+        let recurring = serialize(funds_blocking().recurring());
+        let unset = serialize(funds_blocking());
+
+        assert_eq!(merchant_initiated["is_recurring"], true);
+        assert!(merchant_initiated.get("recurring").is_none());
+        assert_eq!(customer_initiated["is_recurring"], false);
+        assert_eq!(recurring["recurring"], true);
+        assert!(recurring.get("is_recurring").is_none());
+        assert!(unset.get("is_recurring").is_none());
+    }
+
+    #[test]
+    fn mpi_eci_is_sent_as_its_code() {
+        assert_eq!(
+            serialize(funds_blocking().mpi_eci(MpiEci::Success3Ds))["mpi_eci"],
+            "5"
+        );
+        assert_eq!(
+            serialize(funds_blocking().mpi_eci(MpiEci::NotSupported3Ds))["mpi_eci"],
+            "6"
+        );
+        assert_eq!(
+            serialize(funds_blocking().mpi_eci(MpiEci::Without3Ds))["mpi_eci"],
+            "7"
+        );
+        assert!(serialize(funds_blocking()).get("mpi_eci").is_none());
+    }
+
+    fn wallet_payment(token: &str, digital_wallet: DigitalWallet) -> Value {
+        serialize(FundsBlockingRequest::digital_wallet_with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            token,
+            digital_wallet,
+            "order".to_owned(),
+            "description".to_owned(),
+        ))
+    }
+
+    #[test]
+    fn raw_json_wallet_token_is_encoded_once() {
+        let token = r#"{"signature":"MEUCIQ","protocolVersion":"ECv2"}"#;
+        let encoded = general_purpose::STANDARD.encode(token);
+
+        let google_pay = wallet_payment(token, DigitalWallet::GooglePay);
+        let apple_pay = wallet_payment(token, DigitalWallet::ApplePay);
+
+        assert_eq!(google_pay["gpay_token"], encoded);
+        assert_eq!(google_pay["paytype"], "gpay");
+        assert_eq!(apple_pay["applepay_token"], encoded);
+        assert_eq!(apple_pay["paytype"], "apay");
+    }
+
+    #[test]
+    fn already_encoded_wallet_token_is_sent_as_is() {
+        let encoded = general_purpose::STANDARD.encode(r#"{"signature":"MEUCIQ"}"#);
+
+        let google_pay = wallet_payment(&format!(" {encoded}\n"), DigitalWallet::GooglePay);
+
+        assert_eq!(google_pay["gpay_token"], encoded);
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = funds_blocking().sender_email("taras@example.com".to_owned());
+        let invalid = funds_blocking().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serialize(valid.build().unwrap())["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+
+    #[test]
+    fn eci_is_sent_as_its_former_string_value() {
+        let indicators = [
+            (ElectronicCommerceIndicator::MasterCardAuthenticated, "02"),
+            (ElectronicCommerceIndicator::VisaAuthenticated, "05"),
+            (
+                ElectronicCommerceIndicator::MasterCardNotAuthenticated,
+                "06",
+            ),
+            (ElectronicCommerceIndicator::VisaNotAuthenticated, "07"),
+        ];
+
+        for (indicator, eci) in indicators {
+            assert_eq!(
+                serialize(funds_blocking().electronic_commerce_indicator(indicator))["eci"],
+                eci
+            );
+        }
+        assert!(serialize(funds_blocking()).get("eci").is_none());
+    }
+}
@@ -4,8 +4,10 @@ pub use iso3166;
 
 pub mod client;
 pub mod common;
+pub mod error;
 pub mod informational;
 pub mod internet_acquiring;
+pub mod p2p;
 pub mod p2p_credit;
 pub mod p2p_debit;
 pub mod partner;
@@ -0,0 +1,255 @@
+use std::result::Result as StdResult;
+
+use serde::{Deserialize, Serialize};
+use sha3::Sha3_256;
+
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{Action, Currency, Language};
+use crate::common::responses::PaymentResponse;
+use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_max_length, check_order_id, check_positive_amount,
+};
+use crate::error::LiqPayError;
+
+/// Represents a request to perform a money transfer from a sender's card to a receiver's card.
+#[derive(Debug, Serialize)]
+pub struct P2PRequest {
+    public_key: String,
+    action: Action,
+    amount: Amount,
+    currency: Currency,
+    card: CardNumber,
+    card_cvv: CardCvv,
+    card_exp_month: String,
+    card_exp_year: String,
+    receiver_card: CardNumber,
+    order_id: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<Language>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sandbox: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender_first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender_last_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receiver_first_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    receiver_last_name: Option<String>,
+}
+
+/// Represents the card of a sender, from which the money is transferred.
+#[derive(Debug, Clone)]
+pub struct SenderCard {
+    number: CardNumber,
+    cvv: CardCvv,
+    exp_month: String,
+    exp_year: String,
+}
+
+impl SenderCard {
+    /// Constructs a new sender's card from its number, CVV code and expiration month and year,
+    /// e.g. `12` and `30`.
+    pub fn new(number: String, cvv: String, exp_month: String, exp_year: String) -> Self {
+        Self {
+            number: number.into(),
+            cvv: cvv.into(),
+            exp_month,
+            exp_year,
+        }
+    }
+}
+
+impl LiqPayRequest<P2PResponse, Sha3_256> for P2PRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+
+        if self.sandbox.is_none() && config.is_sandbox() {
+            self.sandbox = Some('1');
+        }
+    }
+}
+
+impl P2PRequest {
+    /// Constructs a new request to perform a money transfer from a sender's card to a receiver's card.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
+        sender_card: SenderCard,
+        receiver_card: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        let value = value.into();
+
+        Self {
+            action: Action::P2P,
+            public_key: public_key.into(),
+            amount: value.amount(),
+            currency: value.currency(),
+            card: sender_card.number,
+            card_cvv: sender_card.cvv,
+            card_exp_month: sender_card.exp_month,
+            card_exp_year: sender_card.exp_year,
+            receiver_card: receiver_card.into(),
+            order_id,
+            description,
+            ip: None,
+            language: None,
+            result_url: None,
+            server_url: None,
+            sandbox: None,
+            sender_first_name: None,
+            sender_last_name: None,
+            receiver_first_name: None,
+            receiver_last_name: None,
+        }
+    }
+
+    /// Sets the customer's IP address.
+    pub fn ip(mut self, ip: String) -> Self {
+        self.ip = Some(ip);
+        self
+    }
+
+    /// Sets the customer's language. Allowed values are `uk` - Ukrainian and `en` - English.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the URL of the shop, to which the customer is redirected after completing a purchase.
+    /// Must not exceed 510 characters.
+    pub fn result_url(mut self, url: String) -> Self {
+        self.result_url = Some(url);
+        self
+    }
+
+    /// Sets the API URL, where a notification is sent to on a payment status change.
+    /// Must not exceed 510 characters.
+    pub fn server_url(mut self, url: String) -> Self {
+        self.server_url = Some(url);
+        self
+    }
+
+    /// Sets the sandbox environment for testing purposes.
+    pub fn sandbox(mut self) -> Self {
+        self.sandbox = Some('1');
+        self
+    }
+
+    /// Sets the sender's first name.
+    pub fn sender_first_name(mut self, name: String) -> Self {
+        self.sender_first_name = Some(name);
+        self
+    }
+
+    /// Sets the sender's last name.
+    pub fn sender_last_name(mut self, name: String) -> Self {
+        self.sender_last_name = Some(name);
+        self
+    }
+
+    /// Sets the receiver's first name.
+    pub fn receiver_first_name(mut self, name: String) -> Self {
+        self.receiver_first_name = Some(name);
+        self
+    }
+
+    /// Sets the receiver's last name.
+    pub fn receiver_last_name(mut self, name: String) -> Self {
+        self.receiver_last_name = Some(name);
+        self
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
+}
+
+/// Represents the response to a money transferring operation from a sender's card to a receiver's card.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct P2PResponse {
+    /// Holds the fields shared by the responses to payment operations.
+    #[serde(flatten)]
+    pub payment: PaymentResponse,
+}
+
+impl LiqPayResponse for P2PResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{P2PRequest, SenderCard};
+    use crate::common::enums::Currency;
+
+    fn request() -> P2PRequest {
+        P2PRequest::with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            SenderCard::new(
+                "4242424242424242".to_owned(),
+                "123".to_owned(),
+                "12".to_owned(),
+                "30".to_owned(),
+            ),
+            "5375414100000000".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    #[test]
+    fn request_is_sent_as_p2p_with_both_cards() {
+        let serialized_request: Value = serde_json::to_value(request()).unwrap();
+
+        assert_eq!(serialized_request["action"], "p2p");
+        assert_eq!(serialized_request["card"], "4242424242424242");
+        assert_eq!(serialized_request["card_cvv"], "123");
+        assert_eq!(serialized_request["card_exp_month"], "12");
+        assert_eq!(serialized_request["card_exp_year"], "30");
+        assert_eq!(serialized_request["receiver_card"], "5375414100000000");
+    }
+
+    #[test]
+    fn receiver_names_are_sent_only_when_set() {
+        let unnamed: Value = serde_json::to_value(request()).unwrap();
+        let named: Value = serde_json::to_value(
+            request()
+                .receiver_first_name("Taras".to_owned())
+                .receiver_last_name("Shevchenko".to_owned()),
+        )
+        .unwrap();
+
+        assert!(unnamed.get("receiver_first_name").is_none());
+        assert_eq!(named["receiver_first_name"], "Taras");
+        assert_eq!(named["receiver_last_name"], "Shevchenko");
+    }
+}
@@ -1,17 +1,27 @@
+use std::result::Result as StdResult;
+
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha3::Sha3_256;
 
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::CardNumber;
 use crate::common::enums::{Action, Currency, Language, MpiEci, Result, Status, Version};
-use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::traits::{LiqPayRequest, LiqPayResponse, ThreeDsChallenge};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_customer, check_email, check_max_length, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 
 /// Represents a request to perform a money transfer from a company account to a card.
 #[derive(Debug, Serialize)]
 pub struct P2PCreditRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
     description: String,
@@ -20,8 +30,12 @@ pub struct P2PCreditRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    result_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    sandbox: Option<char>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     taxed: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     receiver_account: Option<String>,
@@ -32,7 +46,7 @@ pub struct P2PCreditRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     receiver_company: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    receiver_card: Option<String>,
+    receiver_card: Option<CardNumber>,
     #[serde(skip_serializing_if = "Option::is_none")]
     receiver_card_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,6 +58,8 @@ pub struct P2PCreditRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     sender_last_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    sender_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sender_country_code: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     sender_city: Option<String>,
@@ -57,44 +73,100 @@ pub struct P2PCreditRequest {
     info: Option<String>,
 }
 
-impl LiqPayRequest<P2PCreditResponse, Sha3_256> for P2PCreditRequest {}
+impl LiqPayRequest<P2PCreditResponse, Sha3_256> for P2PCreditRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+
+        if self.sandbox.is_none() && config.is_sandbox() {
+            self.sandbox = Some('1');
+        }
+    }
+}
 
 impl P2PCreditRequest {
     /// Constructs a new request to perform a money transfer by a card number.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_card_with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
+        card: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        let mut request = Self::new(public_key, value.into(), order_id, description);
+        request.receiver_card = Some(card.into());
+
+        request
+    }
+
+    /// Constructs a new request to perform a money transfer by a card number.
+    #[deprecated(note = "use `by_card_with_value`, which pairs the amount with its currency")]
     pub fn by_card(
         public_key: impl Into<String>,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: Currency,
         card: String,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
-        request.receiver_card = Some(card);
+        Self::by_card_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            order_id,
+            description,
+        )
+    }
+
+    /// Constructs a new request to perform a money transfer by a card token.
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_card_token_with_value(
+        public_key: impl Into<String>,
+        value: impl Into<MonetaryValue>,
+        card_token: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        let mut request = Self::new(public_key, value.into(), order_id, description);
+        request.receiver_card_token = Some(card_token);
 
         request
     }
 
     /// Constructs a new request to perform a money transfer by a card token.
+    #[deprecated(note = "use `by_card_token_with_value`, which pairs the amount with its currency")]
     pub fn by_card_token(
         public_key: impl Into<String>,
-        amount: f64,
+        amount: impl Into<Amount>,
         currency: Currency,
         card_token: String,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
-        request.receiver_card_token = Some(card_token);
-
-        request
+        Self::by_card_token_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card_token,
+            order_id,
+            description,
+        )
     }
 
     /// Constructs a new request to perform a money transfer by an account number.
-    pub fn by_account(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_account_with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         account: String,
         mfo: String,
         okpo: String,
@@ -102,7 +174,7 @@ impl P2PCreditRequest {
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
+        let mut request = Self::new(public_key, value.into(), order_id, description);
 
         request.receiver_account = Some(account);
         request.receiver_mfo = Some(mfo);
@@ -112,6 +184,31 @@ impl P2PCreditRequest {
         request
     }
 
+    /// Constructs a new request to perform a money transfer by an account number.
+    #[deprecated(note = "use `by_account_with_value`, which pairs the amount with its currency")]
+    pub fn by_account(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        account: String,
+        mfo: String,
+        okpo: String,
+        company: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::by_account_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            account,
+            mfo,
+            okpo,
+            company,
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the customer's IP address.
     pub fn ip(mut self, ip: String) -> Self {
         self.ip = Some(ip);
@@ -124,6 +221,13 @@ impl P2PCreditRequest {
         self
     }
 
+    /// Sets the URL of the shop, to which the customer is redirected after completing a purchase.
+    /// Must not exceed 510 characters.
+    pub fn result_url(mut self, url: String) -> Self {
+        self.result_url = Some(url);
+        self
+    }
+
     /// Sets the API URL, where a notification is sent to on a payment status change.
     /// Must not exceed 510 characters.
     pub fn server_url(mut self, url: String) -> Self {
@@ -131,12 +235,30 @@ impl P2PCreditRequest {
         self
     }
 
+    /// Sets the sandbox environment for testing purposes.
+    pub fn sandbox(mut self) -> Self {
+        self.sandbox = Some('1');
+        self
+    }
+
     /// Marks a payment as not subject to tax.
     pub fn taxed(mut self) -> Self {
         self.taxed = Some(String::from("Income is not subject to tax"));
         self
     }
 
+    /// Sets the receiver's first name.
+    pub fn receiver_first_name(mut self, name: String) -> Self {
+        self.receiver_first_name = Some(name);
+        self
+    }
+
+    /// Sets the receiver's last name.
+    pub fn receiver_last_name(mut self, name: String) -> Self {
+        self.receiver_last_name = Some(name);
+        self
+    }
+
     /// Sets the sender's first name.
     pub fn sender_first_name(mut self, name: String) -> Self {
         self.sender_first_name = Some(name);
@@ -149,7 +271,13 @@ impl P2PCreditRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's email.
+    pub fn sender_email(mut self, email: String) -> Self {
+        self.sender_email = Some(email);
+        self
+    }
+
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -185,25 +313,46 @@ impl P2PCreditRequest {
         self
     }
 
+    /// Sets the additional information about the payment as a JSON value,
+    /// which is read back with `info_json` of a status response.
+    pub fn info_json(self, info: Value) -> Self {
+        self.info(info.to_string())
+    }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+        check_customer(self.customer.as_deref())?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
+
     fn new(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: MonetaryValue,
         order_id: String,
         description: String,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::P2PCredit,
             public_key: public_key.into(),
-            amount,
-            currency,
+            amount: value.amount(),
+            currency: value.currency(),
             order_id,
             description,
             receiver_card: None,
             ip: None,
             language: None,
+            result_url: None,
             server_url: None,
+            sandbox: None,
             taxed: None,
             receiver_account: None,
             receiver_mfo: None,
@@ -214,6 +363,7 @@ impl P2PCreditRequest {
             receiver_last_name: None,
             sender_first_name: None,
             sender_last_name: None,
+            sender_email: None,
             sender_country_code: None,
             sender_city: None,
             sender_address: None,
@@ -225,7 +375,7 @@ impl P2PCreditRequest {
 }
 
 /// Represents the response to a money transferring operation from a company account to a card.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct P2PCreditResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -240,18 +390,25 @@ pub struct P2PCreditResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -267,6 +424,7 @@ pub struct P2PCreditResponse {
     /// Represents the end date of the payment.
     pub end_date: Option<u64>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -280,12 +438,15 @@ pub struct P2PCreditResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the 3DS URL for payment confirmation.
     pub redirect_to: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the identifier of a transaction in LiqPay.
     pub transaction_id: Option<u64>,
@@ -303,3 +464,87 @@ pub struct P2PCreditResponse {
 }
 
 impl LiqPayResponse for P2PCreditResponse {}
+
+impl ThreeDsChallenge for P2PCreditResponse {
+    fn challenge_url(&self) -> Option<&str> {
+        self.redirect_to.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::{P2PCreditRequest, P2PCreditResponse};
+    use crate::common::enums::Currency;
+    use crate::common::traits::ThreeDsChallenge;
+    use crate::error::LiqPayError;
+
+    fn request() -> P2PCreditRequest {
+        P2PCreditRequest::by_card_with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    fn serialize(request: P2PCreditRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn receiver_names_are_sent_only_when_set() {
+        let named = serialize(
+            request()
+                .receiver_first_name("Taras".to_owned())
+                .receiver_last_name("Shevchenko".to_owned()),
+        );
+        let unnamed = serialize(request());
+
+        assert_eq!(named["receiver_first_name"], "Taras");
+        assert_eq!(named["receiver_last_name"], "Shevchenko");
+        assert!(unnamed.get("receiver_first_name").is_none());
+        assert!(unnamed.get("receiver_last_name").is_none());
+    }
+
+    #[test]
+    fn result_url_and_sandbox_are_sent_only_when_set() {
+        let url = "https://shop.example.com/result";
+        let set = serialize(request().result_url(url.to_owned()).sandbox());
+        let unset = serialize(request());
+
+        assert_eq!(set["result_url"], url);
+        assert_eq!(set["sandbox"], "1");
+        assert!(unset.get("result_url").is_none());
+        assert!(unset.get("sandbox").is_none());
+    }
+
+    #[test]
+    fn challenge_url_is_redirect_only_when_present() {
+        let redirected: P2PCreditResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "3ds_verify",
+            "redirect_to": "https://acs.example.com",
+        }))
+        .unwrap();
+        let completed: P2PCreditResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "success" })).unwrap();
+
+        assert_eq!(redirected.challenge_url(), Some("https://acs.example.com"));
+        assert_eq!(completed.challenge_url(), None);
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = request().sender_email("taras@example.com".to_owned());
+        let invalid = request().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serialize(valid.build().unwrap())["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+}
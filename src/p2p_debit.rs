@@ -1,24 +1,32 @@
+use std::result::Result as StdResult;
+
 use iso3166::Country;
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
 use crate::common::enums::{Action, Currency, Language, MpiEci, Prepare, Result, Status, Version};
-use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::traits::{LiqPayRequest, LiqPayResponse, ThreeDsChallenge};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_email, check_max_length, check_order_id, check_positive_amount,
+};
+use crate::error::LiqPayError;
 
 /// Represents a request to perform a money transfer from a company account to a beneficiary.
 #[derive(Debug, Serialize)]
 pub struct P2PDebitRequest {
-    version: Version,
     public_key: String,
     action: Action,
-    amount: f64,
+    amount: Amount,
     currency: Currency,
     order_id: String,
     description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card: Option<String>,
+    card: Option<CardNumber>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     card_exp_month: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -26,6 +34,8 @@ pub struct P2PDebitRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     card_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
@@ -63,46 +73,114 @@ pub struct P2PDebitRequest {
     mpi_cres: Option<String>,
 }
 
-impl LiqPayRequest<P2PDebitResponse, Sha3_256> for P2PDebitRequest {}
+impl LiqPayRequest<P2PDebitResponse, Sha3_256> for P2PDebitRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+
+        if self.sandbox.is_none() && config.is_sandbox() {
+            self.sandbox = Some('1');
+        }
+    }
+}
 
 impl P2PDebitRequest {
     /// Constructs a new request to perform a money transfer by a card number.
-    pub fn by_card(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_card_with_value(
         public_key: impl Into<String>,
-        amount: f64,
+        value: impl Into<MonetaryValue>,
         card: String,
         cvv: String,
         exp_month: String,
         exp_year: String,
-        currency: Currency,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
+        let mut request = Self::new(public_key, value.into(), order_id, description);
 
-        request.card = Some(card);
-        request.card_cvv = Some(cvv);
+        request.card = Some(card.into());
+        request.card_cvv = Some(cvv.into());
         request.card_exp_month = Some(exp_month);
         request.card_exp_year = Some(exp_year);
 
         request
     }
 
+    /// Constructs a new request to perform a money transfer by a card number.
+    #[deprecated(note = "use `by_card_with_value`, which pairs the amount with its currency")]
+    pub fn by_card(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        card: String,
+        cvv: String,
+        exp_month: String,
+        exp_year: String,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::by_card_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            cvv,
+            exp_month,
+            exp_year,
+            order_id,
+            description,
+        )
+    }
+
     /// Constructs a new request to perform a money transfer by a card token.
-    pub fn by_token(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn by_token_with_value(
         public_key: impl Into<String>,
-        amount: f64,
+        value: impl Into<MonetaryValue>,
         card_token: String,
-        currency: Currency,
         order_id: String,
         description: String,
     ) -> Self {
-        let mut request = Self::new(public_key, amount, currency, order_id, description);
+        let mut request = Self::new(public_key, value.into(), order_id, description);
         request.card_token = Some(card_token);
 
         request
     }
 
+    /// Constructs a new request to perform a money transfer by a card token.
+    #[deprecated(note = "use `by_token_with_value`, which pairs the amount with its currency")]
+    pub fn by_token(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        card_token: String,
+        currency: Currency,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::by_token_with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card_token,
+            order_id,
+            description,
+        )
+    }
+
+    /// Sets the customer's IP address.
+    pub fn ip(mut self, ip: String) -> Self {
+        self.ip = Some(ip);
+        self
+    }
+
     /// Sets the customer's phone number.
     pub fn phone(mut self, phone: String) -> Self {
         self.phone = Some(phone);
@@ -160,7 +238,13 @@ impl P2PDebitRequest {
         self
     }
 
-    /// Sets the sender's country code in the ISO3166 format.
+    /// Sets the sender's email.
+    pub fn sender_email(mut self, email: String) -> Self {
+        self.sender_email = Some(email);
+        self
+    }
+
+    /// Sets the sender's country code as a numeric ISO 3166-1 code, e.g. `804` for Ukraine.
     pub fn sender_country_code(mut self, country: Country) -> Self {
         self.sender_country_code = Some(country.id.to_string());
         self
@@ -208,26 +292,39 @@ impl P2PDebitRequest {
         self
     }
 
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+
+        if let Some(email) = &self.sender_email {
+            check_email("sender_email", email)?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
+
     fn new(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: MonetaryValue,
         order_id: String,
         description: String,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::P2PDebit,
             public_key: public_key.into(),
-            amount,
+            amount: value.amount(),
             card: None,
             card_cvv: None,
             card_exp_month: None,
             card_exp_year: None,
             card_token: None,
-            currency,
+            currency: value.currency(),
             order_id,
             description,
+            ip: None,
             phone: None,
             language: None,
             prepare: None,
@@ -251,7 +348,7 @@ impl P2PDebitRequest {
 }
 
 /// Represents the response to a money transferring operation from a company account to a beneficiary.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct P2PDebitResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -266,18 +363,25 @@ pub struct P2PDebitResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -293,6 +397,7 @@ pub struct P2PDebitResponse {
     /// Represents the end date of the payment.
     pub end_date: Option<u64>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the Id of an order in the LiqPay system.
     pub liqpay_order_id: Option<String>,
@@ -306,12 +411,15 @@ pub struct P2PDebitResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the 3DS URL for payment confirmation.
     pub redirect_to: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the identifier of a transaction in LiqPay.
     pub transaction_id: Option<u64>,
@@ -329,3 +437,68 @@ pub struct P2PDebitResponse {
 }
 
 impl LiqPayResponse for P2PDebitResponse {}
+
+impl ThreeDsChallenge for P2PDebitResponse {
+    fn challenge_url(&self) -> Option<&str> {
+        self.redirect_to.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{P2PDebitRequest, P2PDebitResponse};
+    use crate::common::enums::Currency;
+    use crate::common::traits::ThreeDsChallenge;
+    use crate::error::LiqPayError;
+
+    fn request() -> P2PDebitRequest {
+        P2PDebitRequest::by_card_with_value(
+            "public_key",
+            (100.0, Currency::UAH),
+            "4242424242424242".to_owned(),
+            "123".to_owned(),
+            "12".to_owned(),
+            "30".to_owned(),
+            "order".to_owned(),
+            "description".to_owned(),
+        )
+    }
+
+    #[test]
+    fn ip_is_sent_only_when_set() {
+        let set = serde_json::to_value(request().ip("192.0.2.1".to_owned())).unwrap();
+        let unset = serde_json::to_value(request()).unwrap();
+
+        assert_eq!(set["ip"], "192.0.2.1");
+        assert!(unset.get("ip").is_none());
+    }
+
+    #[test]
+    fn challenge_url_is_redirect_only_when_present() {
+        let redirected: P2PDebitResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "3ds_verify",
+            "redirect_to": "https://acs.example.com",
+        }))
+        .unwrap();
+        let completed: P2PDebitResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "success" })).unwrap();
+
+        assert_eq!(redirected.challenge_url(), Some("https://acs.example.com"));
+        assert_eq!(completed.challenge_url(), None);
+    }
+
+    #[test]
+    fn sender_email_is_sent_and_validated() {
+        let valid = request().sender_email("taras@example.com".to_owned());
+        let invalid = request().sender_email("taras.example.com".to_owned());
+
+        assert_eq!(
+            serde_json::to_value(valid.build().unwrap()).unwrap()["sender_email"],
+            "taras@example.com"
+        );
+        assert!(matches!(invalid.build(), Err(LiqPayError::Validation(_))));
+    }
+}
@@ -1,13 +1,13 @@
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Language, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::enums::{Action, Language, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 
 /// Represents a request to get a company's information.
 #[derive(Debug, Serialize)]
 pub struct CompanyInformationRequest {
-    version: Version,
     action: Action,
     public_key: String,
     merchant_public_key: String,
@@ -15,13 +15,22 @@ pub struct CompanyInformationRequest {
     language: Option<Language>,
 }
 
-impl LiqPayRequest<CompanyInformationResponse, Sha3_256> for CompanyInformationRequest {}
+impl LiqPayRequest<CompanyInformationResponse, Sha3_256> for CompanyInformationRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl CompanyInformationRequest {
     /// Construct a new request to get a company's information.
     pub fn new(public_key: impl Into<String>, merchant_public_key: impl Into<String>) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::MerchantInfo,
             public_key: public_key.into(),
             merchant_public_key: merchant_public_key.into(),
@@ -37,7 +46,7 @@ impl CompanyInformationRequest {
 }
 
 /// Represents a response to getting a company's information operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CompanyInformationResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
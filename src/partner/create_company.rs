@@ -1,12 +1,24 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::result::Result as StdResult;
+
+use base64::{Engine, engine::general_purpose};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use sha1::Sha1;
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, Result, Status, Version};
+use crate::client::ClientConfig;
+use crate::common::enums::{Action, Currency, Language, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{check_iban, check_url};
+use crate::error::LiqPayError;
 
 /// Represents the company's contact details
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct LawContacts {
     phone: Option<String>,
     email: Option<String>,
@@ -35,7 +47,7 @@ impl LawContacts {
 }
 
 /// Represents the company CTO's information.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct LawCtoInformation {
     name: Option<String>,
     inn: Option<String>,
@@ -88,7 +100,7 @@ impl LawCtoInformation {
 }
 
 /// Represents the company's owner information.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct LawOwnerInformation {
     name: Option<String>,
     inn: Option<String>,
@@ -149,7 +161,7 @@ impl LawOwnerInformation {
 }
 
 /// Represents the company co-owner's information.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct LawCoOwnerInformation {
     name: Option<String>,
     inn: Option<String>,
@@ -228,7 +240,6 @@ impl LawCoOwnerInformation {
 /// Represents a request to create a company.
 #[derive(Debug, Serialize)]
 pub struct CreateCompanyRequest {
-    version: Version,
     public_key: String,
     action: Action,
     description: String,
@@ -265,7 +276,11 @@ pub struct CreateCompanyRequest {
     public_phone: Option<String>,
 }
 
-impl LiqPayRequest<CreateCompanyResponse, Sha3_256> for CreateCompanyRequest {}
+impl LiqPayRequest<CreateCompanyResponse, Sha3_256> for CreateCompanyRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl CreateCompanyRequest {
     /// Construct a new request to create a company.
@@ -288,7 +303,6 @@ impl CreateCompanyRequest {
         law_co_owners_info: Vec<LawCoOwnerInformation>,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::CreateShop,
             public_key: public_key.into(),
             description,
@@ -316,6 +330,35 @@ impl CreateCompanyRequest {
         }
     }
 
+    /// Constructs a builder of a request to create a company, which sets the required fields by name.
+    pub fn builder(
+        public_key: impl Into<String>,
+    ) -> CreateCompanyRequestBuilder<steps::Description> {
+        let request = Self::new(
+            public_key,
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            LawContacts::new(),
+            LawCtoInformation::new(),
+            Vec::new(),
+            Vec::new(),
+        );
+
+        CreateCompanyRequestBuilder {
+            request,
+            step: PhantomData,
+        }
+    }
+
     /// Sets the percentage of an agent fee.
     pub fn amount_percent_agent(mut self, percent: f32) -> Self {
         self.amount_percent_agent = Some(percent);
@@ -353,10 +396,291 @@ impl CreateCompanyRequest {
         self.public_phone = Some(phone);
         self
     }
+
+    /// Validates the request. Fails if either IBAN has an invalid checksum
+    /// or the website or the logo is not a valid URL.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_iban("iban", &self.iban)?;
+        check_iban("law_iban", &self.law_iban)?;
+        check_url("site", &self.site)?;
+
+        if let Some(logo) = &self.logo {
+            check_url("logo", logo)?;
+        }
+
+        Ok(self)
+    }
+}
+
+/// Contains the steps of `CreateCompanyRequestBuilder`.
+pub mod steps {
+    /// Represents the step of setting `description`.
+    #[derive(Debug)]
+    pub enum Description {}
+
+    /// Represents the step of setting `email`.
+    #[derive(Debug)]
+    pub enum Email {}
+
+    /// Represents the step of setting `name`.
+    #[derive(Debug)]
+    pub enum Name {}
+
+    /// Represents the step of setting `phone`.
+    #[derive(Debug)]
+    pub enum Phone {}
+
+    /// Represents the step of setting `site`.
+    #[derive(Debug)]
+    pub enum Site {}
+
+    /// Represents the step of setting `iban`.
+    #[derive(Debug)]
+    pub enum Iban {}
+
+    /// Represents the step of setting `company`.
+    #[derive(Debug)]
+    pub enum Company {}
+
+    /// Represents the step of setting `okpo`.
+    #[derive(Debug)]
+    pub enum Okpo {}
+
+    /// Represents the step of setting `law_okpo`.
+    #[derive(Debug)]
+    pub enum LawOkpo {}
+
+    /// Represents the step of setting `law_name`.
+    #[derive(Debug)]
+    pub enum LawName {}
+
+    /// Represents the step of setting `law_iban`.
+    #[derive(Debug)]
+    pub enum LawIban {}
+
+    /// Represents the step of setting `law_contacts`.
+    #[derive(Debug)]
+    pub enum LawContacts {}
+
+    /// Represents the step of setting `law_cto_info`.
+    #[derive(Debug)]
+    pub enum LawCtoInformation {}
+
+    /// Represents the step of setting `law_owners_info`.
+    #[derive(Debug)]
+    pub enum LawOwnersInformation {}
+
+    /// Represents the step of setting `law_co_owners_info`.
+    #[derive(Debug)]
+    pub enum LawCoOwnersInformation {}
+
+    /// Represents the step, at which all the required fields are set.
+    #[derive(Debug)]
+    pub enum Ready {}
+}
+
+/// Represents a builder of a request to create a company.
+/// Every required field is set by its own step, so the request can only be built
+/// once all of them are provided.
+///
+/// # Examples
+///
+/// ```
+/// use liqpay_rs::partner::create_company::{CreateCompanyRequest, LawContacts, LawCtoInformation};
+///
+/// let request = CreateCompanyRequest::builder("public_key")
+///     .description("Online store".to_owned())
+///     .email("shop@example.com".to_owned())
+///     .name("Shop".to_owned())
+///     .phone("380501234567".to_owned())
+///     .site("https://example.com".to_owned())
+///     .iban("UA213223130000026007233566001".to_owned())
+///     .company("Shop LLC".to_owned())
+///     .okpo("12345678".to_owned())
+///     .law_okpo("87654321".to_owned())
+///     .law_name("Shop Limited Liability Company".to_owned())
+///     .law_iban("UA213223130000026007233566001".to_owned())
+///     .law_contacts(LawContacts::new())
+///     .law_cto_info(LawCtoInformation::new())
+///     .law_owners_info(Vec::new())
+///     .law_co_owners_info(Vec::new())
+///     .build();
+///
+/// assert!(request.is_ok());
+/// ```
+///
+/// Skipping a required field, e.g. `okpo`, does not compile:
+///
+/// ```compile_fail
+/// use liqpay_rs::partner::create_company::CreateCompanyRequest;
+///
+/// let request = CreateCompanyRequest::builder("public_key")
+///     .description("Online store".to_owned())
+///     .email("shop@example.com".to_owned())
+///     .name("Shop".to_owned())
+///     .phone("380501234567".to_owned())
+///     .site("https://example.com".to_owned())
+///     .iban("UA213223130000026007233566001".to_owned())
+///     .company("Shop LLC".to_owned())
+///     .law_okpo("87654321".to_owned());
+/// ```
+#[derive(Debug)]
+pub struct CreateCompanyRequestBuilder<Step> {
+    request: CreateCompanyRequest,
+    step: PhantomData<Step>,
+}
+
+impl<Step> CreateCompanyRequestBuilder<Step> {
+    fn next<NextStep>(self) -> CreateCompanyRequestBuilder<NextStep> {
+        CreateCompanyRequestBuilder {
+            request: self.request,
+            step: PhantomData,
+        }
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Description> {
+    /// Sets the company's description.
+    pub fn description(mut self, description: String) -> CreateCompanyRequestBuilder<steps::Email> {
+        self.request.description = description;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Email> {
+    /// Sets the company's email.
+    pub fn email(mut self, email: String) -> CreateCompanyRequestBuilder<steps::Name> {
+        self.request.email = email;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Name> {
+    /// Sets the company's name.
+    pub fn name(mut self, name: String) -> CreateCompanyRequestBuilder<steps::Phone> {
+        self.request.name = name;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Phone> {
+    /// Sets the company's phone number.
+    pub fn phone(mut self, phone: String) -> CreateCompanyRequestBuilder<steps::Site> {
+        self.request.phone = phone;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Site> {
+    /// Sets the company's website.
+    pub fn site(mut self, site: String) -> CreateCompanyRequestBuilder<steps::Iban> {
+        self.request.site = site;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Iban> {
+    /// Sets the company's IBAN.
+    pub fn iban(mut self, iban: String) -> CreateCompanyRequestBuilder<steps::Company> {
+        self.request.iban = iban;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Company> {
+    /// Sets the company's full name.
+    pub fn company(mut self, company: String) -> CreateCompanyRequestBuilder<steps::Okpo> {
+        self.request.company = company;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Okpo> {
+    /// Sets the company's EDRPOU or TIN.
+    pub fn okpo(mut self, okpo: String) -> CreateCompanyRequestBuilder<steps::LawOkpo> {
+        self.request.okpo = okpo;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawOkpo> {
+    /// Sets the EDRPOU or TIN of the legal entity.
+    pub fn law_okpo(mut self, law_okpo: String) -> CreateCompanyRequestBuilder<steps::LawName> {
+        self.request.law_okpo = law_okpo;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawName> {
+    /// Sets the name of the legal entity.
+    pub fn law_name(mut self, law_name: String) -> CreateCompanyRequestBuilder<steps::LawIban> {
+        self.request.law_name = law_name;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawIban> {
+    /// Sets the IBAN of the legal entity.
+    pub fn law_iban(mut self, law_iban: String) -> CreateCompanyRequestBuilder<steps::LawContacts> {
+        self.request.law_iban = law_iban;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawContacts> {
+    /// Sets the contact details of the legal entity.
+    pub fn law_contacts(
+        mut self,
+        law_contacts: LawContacts,
+    ) -> CreateCompanyRequestBuilder<steps::LawCtoInformation> {
+        self.request.law_contacts = law_contacts;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawCtoInformation> {
+    /// Sets the information about the company's CTO.
+    pub fn law_cto_info(
+        mut self,
+        law_cto_info: LawCtoInformation,
+    ) -> CreateCompanyRequestBuilder<steps::LawOwnersInformation> {
+        self.request.law_cto_info = law_cto_info;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawOwnersInformation> {
+    /// Sets the information about the company's owners.
+    pub fn law_owners_info(
+        mut self,
+        law_owners_info: Vec<LawOwnerInformation>,
+    ) -> CreateCompanyRequestBuilder<steps::LawCoOwnersInformation> {
+        self.request.law_owners_info = law_owners_info;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::LawCoOwnersInformation> {
+    /// Sets the information about the company's co-owners.
+    pub fn law_co_owners_info(
+        mut self,
+        law_co_owners_info: Vec<LawCoOwnerInformation>,
+    ) -> CreateCompanyRequestBuilder<steps::Ready> {
+        self.request.law_co_owners_info = law_co_owners_info;
+        self.next()
+    }
+}
+
+impl CreateCompanyRequestBuilder<steps::Ready> {
+    /// Builds the request to create a company. Fails if either IBAN has an invalid checksum
+    /// or the website or the logo is not a valid URL.
+    pub fn build(self) -> StdResult<CreateCompanyRequest, LiqPayError> {
+        self.request.build()
+    }
 }
 
 /// Represents a response to editing a company's information operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CreateCompanyResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -376,6 +700,10 @@ pub struct CreateCompanyResponse {
 
 impl LiqPayResponse for CreateCompanyResponse {}
 
+/// The maximum size of a base64-encoded document. LiqPay accepts documents only inline
+/// in the request data, so larger scans should be compressed before being attached.
+pub const DOCUMENT_MAX_SIZE: usize = 5 * 1024 * 1024;
+
 /// Represents a required document for a company activation in accordance with an MCC code.
 #[derive(Debug, Serialize)]
 pub struct Document {
@@ -397,30 +725,66 @@ impl Document {
         }
     }
 
+    /// Construct a new instance of a document from its raw content, which is encoded to base64.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>, name: String, file_name: String) -> Self {
+        Self::new(general_purpose::STANDARD.encode(bytes), name, file_name)
+    }
+
+    /// Construct a new instance of a document from a file. The file name is taken from the path.
+    pub fn from_path(path: impl AsRef<Path>, name: String) -> io::Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Self::from_bytes(bytes, name, file_name))
+    }
+
     /// Sets the identifier of a document.
     pub fn document_id(mut self, id: String) -> Self {
         self.document_id = Some(id);
         self
     }
+
+    fn check_size(&self) -> StdResult<(), LiqPayError> {
+        if self.file.len() <= DOCUMENT_MAX_SIZE {
+            Ok(())
+        } else {
+            Err(LiqPayError::Validation(format!(
+                "the document `{}` must not exceed {} bytes after base64 encoding",
+                self.file_name, DOCUMENT_MAX_SIZE
+            )))
+        }
+    }
 }
 
 /// Represents a request to get available MCC codes.
 #[derive(Debug, Serialize)]
 pub struct MccCodesRequest {
     action: Action,
-    version: Version,
     public_key: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
 }
 
-impl LiqPayRequest<MccCodesResponse, Sha1> for MccCodesRequest {}
+impl LiqPayRequest<MccCodesResponse, Sha1> for MccCodesRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl MccCodesRequest {
     /// Construct a new request to get available MCC codes.
     pub fn new(public_key: impl Into<String>) -> Self {
         Self {
-            version: Version::Three,
             action: Action::MccCodes,
             public_key: public_key.into(),
             language: None,
@@ -436,7 +800,7 @@ impl MccCodesRequest {
 }
 
 /// Represents an MCC code.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MccCode {
     /// Represents the identifier of a code.
     pub id: u32,
@@ -449,7 +813,7 @@ pub struct MccCode {
 }
 
 /// Represents a response to getting available MCC codes operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MccCodesResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -471,20 +835,28 @@ impl LiqPayResponse for MccCodesResponse {}
 #[derive(Debug, Serialize)]
 pub struct MccDocumentsRequest {
     action: Action,
-    version: Version,
     public_key: String,
     mcc_code: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     language: Option<Language>,
 }
 
-impl LiqPayRequest<MccDocumentsResponse, Sha1> for MccDocumentsRequest {}
+impl LiqPayRequest<MccDocumentsResponse, Sha1> for MccDocumentsRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl MccDocumentsRequest {
     /// Construct a new request to get documents for an MCC codes.
     pub fn new(public_key: impl Into<String>, mcc_code: u32) -> Self {
         Self {
-            version: Version::Three,
             action: Action::MccCodes,
             public_key: public_key.into(),
             mcc_code,
@@ -501,7 +873,7 @@ impl MccDocumentsRequest {
 }
 
 /// Represents a document type.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum DocumentType {
     #[serde(rename = "required")]
     Required,
@@ -512,7 +884,7 @@ pub enum DocumentType {
 }
 
 /// Represents an MCC document.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MccDocument {
     /// Represents the identifier of a document.
     pub doc_id: u32,
@@ -528,7 +900,7 @@ pub struct MccDocument {
 }
 
 /// Represents a response to getting documents for an MCC code operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MccDocumentsResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -557,7 +929,6 @@ pub enum UrlCallbackStatus {
 /// Represents a request to register a company.
 #[derive(Debug, Serialize)]
 pub struct RegisterCompanyRequest {
-    version: Version,
     public_key: String,
     action: Action,
     phone: String,
@@ -600,12 +971,17 @@ pub struct RegisterCompanyRequest {
     enable_reports: Option<String>,
     #[serde(rename = "can_checkout_edit", skip_serializing_if = "Option::is_none")]
     enable_checkout_edit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     logo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     url_callback_status: Option<UrlCallbackStatus>,
 }
 
-impl LiqPayRequest<RegisterCompanyResponse, Sha3_256> for RegisterCompanyRequest {}
+impl LiqPayRequest<RegisterCompanyResponse, Sha3_256> for RegisterCompanyRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl RegisterCompanyRequest {
     /// Construct a new request to register a company with commission specified as a percentage.
@@ -766,6 +1142,36 @@ impl RegisterCompanyRequest {
         self
     }
 
+    /// Validates the request. Fails if either IBAN has an invalid checksum, a document exceeds
+    /// `DOCUMENT_MAX_SIZE` after base64 encoding or any of the URLs, including the logo, is not valid.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_iban("iban", &self.iban)?;
+        check_iban("law_iban", &self.law_iban)?;
+
+        for document in self.documents.iter().flatten() {
+            document.check_size()?;
+        }
+
+        let urls = [
+            ("url_site", &self.site_url),
+            ("url_app_iphone", &self.iphone_app_url),
+            ("url_app_android", &self.android_app_url),
+            ("telegram", &self.telegram),
+            ("viber", &self.viber),
+            ("instagram", &self.instagram),
+            ("facebook", &self.facebook),
+            ("logo", &self.logo),
+        ];
+
+        for (field, url) in urls {
+            if let Some(url) = url {
+                check_url(field, url)?;
+            }
+        }
+
+        Ok(self)
+    }
+
     fn new(
         public_key: impl Into<String>,
         email: String,
@@ -784,7 +1190,6 @@ impl RegisterCompanyRequest {
         law_co_owners_info: Vec<LawCoOwnerInformation>,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::RegisterShop,
             public_key: public_key.into(),
             email,
@@ -820,18 +1225,228 @@ impl RegisterCompanyRequest {
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Represents the response to a company registration operation.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RegisterCompanyResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
     /// Represents the status of the request.
     pub status: Option<Status>,
+    /// Represents the private key of the registered company.
+    pub private_key: Option<String>,
+    /// Represents the public key of the registered company.
+    pub public_key: Option<String>,
     /// Holds an error code.
     #[serde(rename = "err_code")]
     pub error_code: Option<String>,
     /// Holds an error description.
     #[serde(rename = "err_description")]
     pub error_description: Option<String>,
+    /// Holds the fields, which are not modeled by the response, e.g. the identifier
+    /// of a pending application, which is needed to track the activation of the company.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
 }
 
 impl LiqPayResponse for RegisterCompanyResponse {}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use base64::{Engine, engine::general_purpose};
+    use serde_json::{Value, json};
+
+    use super::{
+        CreateCompanyRequest, DOCUMENT_MAX_SIZE, Document, LawCoOwnerInformation, LawContacts,
+        LawCtoInformation, LawOwnerInformation, RegisterCompanyRequest, RegisterCompanyResponse,
+    };
+    use crate::error::LiqPayError;
+
+    const IBAN: &str = "UA213223130000026007233566001";
+
+    fn create_request() -> CreateCompanyRequest {
+        CreateCompanyRequest::new(
+            "public_key",
+            "description".to_owned(),
+            "shop@example.com".to_owned(),
+            "Shop".to_owned(),
+            "380950000001".to_owned(),
+            "https://shop.example.com".to_owned(),
+            IBAN.to_owned(),
+            "Company".to_owned(),
+            "12345678".to_owned(),
+            "12345678".to_owned(),
+            "Company LLC".to_owned(),
+            IBAN.to_owned(),
+            LawContacts::new(),
+            LawCtoInformation::new(),
+            vec![LawOwnerInformation::new()],
+            Vec::new(),
+        )
+    }
+
+    fn register_request() -> RegisterCompanyRequest {
+        RegisterCompanyRequest::with_percent_commission(
+            "public_key",
+            "shop@example.com".to_owned(),
+            "Shop".to_owned(),
+            "380950000001".to_owned(),
+            IBAN.to_owned(),
+            "Company".to_owned(),
+            "12345678".to_owned(),
+            "5999".to_owned(),
+            "12345678".to_owned(),
+            "Company LLC".to_owned(),
+            IBAN.to_owned(),
+            LawContacts::new(),
+            LawCtoInformation::new(),
+            vec![LawOwnerInformation::new()],
+            Vec::new(),
+            1.5,
+        )
+    }
+
+    fn serialize(request: RegisterCompanyRequest) -> Value {
+        serde_json::to_value(request).unwrap()
+    }
+
+    #[test]
+    fn unset_logo_is_not_sent() {
+        let unset = serialize(register_request());
+        let set =
+            serialize(register_request().logo("https://shop.example.com/logo.png".to_owned()));
+
+        assert!(unset.get("logo").is_none());
+        assert_eq!(set["logo"], "https://shop.example.com/logo.png");
+    }
+
+    #[test]
+    fn invalid_urls_are_rejected() {
+        let register = register_request()
+            .site_url("shop.example.com".to_owned())
+            .build();
+        let create = create_request().logo("not a url".to_owned()).build();
+
+        assert!(
+            matches!(register, Err(LiqPayError::Validation(message)) if message.contains("url_site"))
+        );
+        assert!(
+            matches!(create, Err(LiqPayError::Validation(message)) if message.contains("logo"))
+        );
+    }
+
+    #[test]
+    fn valid_urls_are_accepted() {
+        let register = register_request()
+            .site_url("https://shop.example.com".to_owned())
+            .logo("https://shop.example.com/logo.png".to_owned())
+            .build();
+        let create = create_request()
+            .logo("https://shop.example.com/logo.png".to_owned())
+            .build();
+
+        assert!(register.is_ok());
+        assert!(create.is_ok());
+    }
+
+    #[test]
+    fn document_bytes_are_sent_base64_encoded() {
+        let bytes = [0_u8, 1, 2, 254, 255];
+
+        let document = serde_json::to_value(Document::from_bytes(
+            bytes,
+            "passport".to_owned(),
+            "passport.pdf".to_owned(),
+        ))
+        .unwrap();
+
+        let file = document["file"].as_str().unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(file).unwrap(), bytes);
+        assert_eq!(document["file_name"], "passport.pdf");
+    }
+
+    #[test]
+    fn document_file_name_is_taken_from_path() {
+        let path = std::env::temp_dir().join(format!("liqpay_document_{}.pdf", std::process::id()));
+        fs::write(&path, b"content").unwrap();
+
+        let document = Document::from_path(&path, "passport".to_owned());
+        fs::remove_file(&path).unwrap();
+
+        let document = serde_json::to_value(document.unwrap()).unwrap();
+        let file = document["file"].as_str().unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(file).unwrap(), b"content");
+        assert_eq!(
+            document["file_name"],
+            path.file_name().unwrap().to_str().unwrap()
+        );
+        assert_eq!(document["name"], "passport");
+    }
+
+    #[test]
+    fn inline_document_over_size_limit_is_rejected() {
+        let document = |size: usize| {
+            Document::new(
+                "a".repeat(size),
+                "passport".to_owned(),
+                "passport.pdf".to_owned(),
+            )
+        };
+
+        let at_limit = register_request()
+            .documents(vec![document(DOCUMENT_MAX_SIZE)])
+            .build();
+        let over_limit = register_request()
+            .documents(vec![document(16), document(DOCUMENT_MAX_SIZE + 1)])
+            .build();
+
+        assert!(at_limit.is_ok());
+        assert!(matches!(
+            over_limit,
+            Err(LiqPayError::Validation(message)) if message.contains("passport.pdf")
+        ));
+    }
+
+    #[test]
+    fn law_information_is_constructed_via_default() {
+        let contacts = LawContacts {
+            email: Some("shop@example.com".to_owned()),
+            ..Default::default()
+        };
+        let cto = LawCtoInformation::default().name("CTO".to_owned());
+        let owner = LawOwnerInformation::default();
+        let co_owner = LawCoOwnerInformation::default().company_okpo("12345678".to_owned());
+
+        assert_eq!(
+            serde_json::to_value(&contacts).unwrap(),
+            serde_json::to_value(LawContacts::new().email("shop@example.com".to_owned())).unwrap()
+        );
+        assert_eq!(serde_json::to_value(&cto).unwrap()["name"], "CTO");
+        assert_eq!(
+            serde_json::to_value(&owner).unwrap(),
+            serde_json::to_value(LawOwnerInformation::new()).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&co_owner).unwrap()["company_okpo"],
+            "12345678"
+        );
+    }
+
+    #[test]
+    fn registration_success_body_is_deserialized() {
+        let response: RegisterCompanyResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "public_key": "company_public_key",
+            "private_key": "company_private_key",
+            "application_id": 12345,
+        }))
+        .unwrap();
+
+        assert_eq!(response.public_key.as_deref(), Some("company_public_key"));
+        assert_eq!(response.private_key.as_deref(), Some("company_private_key"));
+        assert_eq!(response.extra["application_id"], 12345);
+        assert_eq!(response.error_code, None);
+    }
+}
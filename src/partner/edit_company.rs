@@ -1,13 +1,12 @@
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Result, Status, Version};
+use crate::common::enums::{Action, Currency, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 
 /// Represents a request to edit a company's information.
 #[derive(Debug, Serialize)]
 pub struct EditCompanyRequest {
-    version: Version,
     public_key: String,
     action: Action,
     description: String,
@@ -38,7 +37,11 @@ pub struct EditCompanyRequest {
     public_phone: Option<String>,
 }
 
-impl LiqPayRequest<EditCompanyResponse, Sha3_256> for EditCompanyRequest {}
+impl LiqPayRequest<EditCompanyResponse, Sha3_256> for EditCompanyRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl EditCompanyRequest {
     /// Construct a new request to edit a company's information.
@@ -55,7 +58,6 @@ impl EditCompanyRequest {
         okpo: String,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::EditShop,
             public_key: public_key.into(),
             description,
@@ -117,7 +119,7 @@ impl EditCompanyRequest {
 }
 
 /// Represents a response to editing a company's information operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct EditCompanyResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -136,3 +138,47 @@ pub struct EditCompanyResponse {
 }
 
 impl LiqPayResponse for EditCompanyResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::EditCompanyResponse;
+    use crate::common::enums::{Result, Status};
+
+    #[test]
+    fn success_body_is_deserialized() {
+        let response: EditCompanyResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "public_key": "company_public_key",
+            "private_key": "company_private_key",
+        }))
+        .unwrap();
+
+        assert_eq!(response.result, Result::Ok);
+        assert_eq!(response.status, Status::Success);
+        assert_eq!(response.public_key.as_deref(), Some("company_public_key"));
+        assert_eq!(response.private_key.as_deref(), Some("company_private_key"));
+        assert_eq!(response.error_code, None);
+    }
+
+    #[test]
+    fn error_body_is_deserialized() {
+        let response: EditCompanyResponse = serde_json::from_value(json!({
+            "result": "error",
+            "status": "error",
+            "err_code": "public_key_not_found",
+            "err_description": "Public key not found",
+        }))
+        .unwrap();
+
+        assert_eq!(response.result, Result::Error);
+        assert_eq!(response.error_code.as_deref(), Some("public_key_not_found"));
+        assert_eq!(
+            response.error_description.as_deref(),
+            Some("Public key not found")
+        );
+        assert_eq!(response.public_key, None);
+    }
+}
@@ -1,25 +1,27 @@
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Result, Status, Version};
+use crate::common::enums::{Action, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 
 /// Represents a request to get a partner's information.
 #[derive(Debug, Serialize)]
 pub struct PartnerInformationRequest {
-    version: Version,
     action: Action,
     public_key: String,
     phone: String,
 }
 
-impl LiqPayRequest<PartnerInformationResponse, Sha3_256> for PartnerInformationRequest {}
+impl LiqPayRequest<PartnerInformationResponse, Sha3_256> for PartnerInformationRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl PartnerInformationRequest {
     /// Construct a new request to get a partner's information.
     pub fn new(public_key: impl Into<String>, phone: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::UserInfo,
             public_key: public_key.into(),
             phone,
@@ -28,7 +30,7 @@ impl PartnerInformationRequest {
 }
 
 /// Represents a response to to getting a partner's information operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PartnerInformationResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
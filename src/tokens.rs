@@ -1,13 +1,17 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Result, Status, Version};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{Action, Result, Status};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::check_customer;
+use crate::error::LiqPayError;
 
 /// Represents a token creation request.
 #[derive(Debug, Serialize)]
 pub struct CreateTokenRequest {
-    version: Version,
     public_key: String,
     action: Action,
     is_debit: bool,
@@ -19,9 +23,9 @@ pub struct CreateTokenRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     customer: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card: Option<String>,
+    card: Option<CardNumber>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     card_exp_month: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,7 +34,11 @@ pub struct CreateTokenRequest {
     expiration_date: Option<String>,
 }
 
-impl LiqPayRequest<CreateTokenResponse, Sha3_256> for CreateTokenRequest {}
+impl LiqPayRequest<CreateTokenResponse, Sha3_256> for CreateTokenRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl CreateTokenRequest {
     /// Constructs a new request to create a card token using Token connect control.
@@ -71,8 +79,8 @@ impl CreateTokenRequest {
     ) -> Self {
         let mut request = Self::new(public_key, is_debit);
 
-        request.card = Some(card);
-        request.card_cvv = Some(cvv);
+        request.card = Some(card.into());
+        request.card_cvv = Some(cvv.into());
         request.card_exp_month = Some(exp_month);
         request.card_exp_year = Some(exp_year);
 
@@ -87,9 +95,15 @@ impl CreateTokenRequest {
         self
     }
 
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_customer(self.customer.as_deref())?;
+
+        Ok(self)
+    }
+
     fn new(public_key: impl Into<String>, is_debit: bool) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::CreateToken,
             public_key: public_key.into(),
             is_debit,
@@ -107,7 +121,7 @@ impl CreateTokenRequest {
 }
 
 /// Represents a card token information status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum CardTokenInfoStatus {
     #[serde(rename = "INACTIVE")]
     Inactive,
@@ -120,8 +134,8 @@ pub enum CardTokenInfoStatus {
 }
 
 /// Represents a card token information description.
-#[derive(Debug, Deserialize)]
-pub enum CardtokenInfoDescription {
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum CardTokenInfoDescription {
     #[serde(rename = "APPROVED")]
     Approved,
     #[serde(rename = "REQUIRE_ADDITIONAL_AUTHENTICATION")]
@@ -134,8 +148,12 @@ pub enum CardtokenInfoDescription {
     Error,
 }
 
+/// Represents a card token information description.
+#[deprecated(note = "use `CardTokenInfoDescription` instead")]
+pub type CardtokenInfoDescription = CardTokenInfoDescription;
+
 /// Represents card token information.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CardTokenInfo {
     /// Represents a token reference.
     #[serde(rename = "tokenRef")]
@@ -149,11 +167,19 @@ pub struct CardTokenInfo {
     /// Represents a token status.
     pub status: Option<CardTokenInfoStatus>,
     /// Represents the payment decision regarding card digitization for MasterCard-only payments.
-    pub decision: CardtokenInfoDescription,
+    /// Absent for other cards.
+    pub decision: Option<CardTokenInfoDescription>,
+}
+
+impl CardTokenInfo {
+    /// Returns the payment decision regarding card digitization, if LiqPay has sent one.
+    pub fn decision(&self) -> Option<&CardTokenInfoDescription> {
+        self.decision.as_ref()
+    }
 }
 
 /// Represents a token creation response.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CreateTokenResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -187,14 +213,17 @@ pub enum CardTokenAction {
 /// Represents a request to change a token status.
 #[derive(Debug, Serialize)]
 pub struct ChangeTokenStatusRequest {
-    version: Version,
     public_key: String,
     action: Action,
     card_token: String,
     card_token_action: CardTokenAction,
 }
 
-impl LiqPayRequest<ChangeTokenStatusResponse, Sha3_256> for ChangeTokenStatusRequest {}
+impl LiqPayRequest<ChangeTokenStatusResponse, Sha3_256> for ChangeTokenStatusRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl ChangeTokenStatusRequest {
     /// Constructs a new request to change a token status.
@@ -204,7 +233,6 @@ impl ChangeTokenStatusRequest {
         card_token_action: CardTokenAction,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::UpdateToken,
             public_key: public_key.into(),
             card_token,
@@ -214,7 +242,7 @@ impl ChangeTokenStatusRequest {
 }
 
 /// Represents a response of changing a token status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ChangeTokenStatusResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -233,3 +261,75 @@ pub struct ChangeTokenStatusResponse {
 }
 
 impl LiqPayResponse for ChangeTokenStatusResponse {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        CardTokenInfoDescription, CardTokenInfoStatus, CreateTokenRequest, CreateTokenResponse,
+    };
+    use crate::common::validation::CUSTOMER_MAX_LENGTH;
+    use crate::error::LiqPayError;
+
+    fn response(card_token_info: serde_json::Value) -> CreateTokenResponse {
+        serde_json::from_value(json!({
+            "result": "ok",
+            "status": "success",
+            "card_token": "card_token",
+            "card_token_info": card_token_info,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn token_response_with_decision_is_deserialized() {
+        let response = response(json!({
+            "tokenRef": "reference",
+            "tokenSuffix": "4242",
+            "status": "ACTIVE",
+            "decision": "APPROVED",
+        }));
+        let info = response.card_token_info.unwrap();
+
+        assert_eq!(info.status, Some(CardTokenInfoStatus::Active));
+        assert_eq!(info.decision(), Some(&CardTokenInfoDescription::Approved));
+    }
+
+    #[test]
+    fn token_response_without_decision_is_deserialized() {
+        let response = response(json!({
+            "tokenRef": "reference",
+            "tokenSuffix": "4242",
+            "status": "ACTIVE",
+        }));
+
+        assert_eq!(response.card_token_info.unwrap().decision(), None);
+    }
+
+    fn enrollment(customer: &str) -> CreateTokenRequest {
+        CreateTokenRequest::visa_cards_enrollment_hub(
+            "public_key",
+            false,
+            "push_data".to_owned(),
+            customer.to_owned(),
+        )
+    }
+
+    #[test]
+    fn customer_over_limit_is_rejected() {
+        let customer = "c".repeat(CUSTOMER_MAX_LENGTH + 1);
+
+        assert!(matches!(
+            enrollment(&customer).build(),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn customer_within_limit_is_accepted() {
+        let customer = "c".repeat(CUSTOMER_MAX_LENGTH);
+
+        assert!(enrollment(&customer).build().is_ok());
+    }
+}
@@ -1,22 +1,27 @@
+use std::result::Result as StdResult;
+
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
+use crate::client::ClientConfig;
+use crate::common::card::{CardCvv, CardNumber};
 use crate::common::enums::{Action, Currency, Language, MpiEci, PayType, Result, Status, Version};
 use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::common::validation::{DESCRIPTION_MAX_LENGTH, check_max_length, check_order_id};
+use crate::error::LiqPayError;
 
 /// Represents a request to verify a card.
 #[derive(Debug, Serialize)]
 pub struct CardVerificationRequest {
-    version: Version,
     action: Action,
     public_key: String,
-    card: String,
+    card: CardNumber,
     card_exp_month: String,
     card_exp_year: String,
     order_id: String,
     description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     ip: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -25,7 +30,21 @@ pub struct CardVerificationRequest {
     verify_code: Option<char>,
 }
 
-impl LiqPayRequest<CardVerificationResponse, Sha3_256> for CardVerificationRequest {}
+impl LiqPayRequest<CardVerificationResponse, Sha3_256> for CardVerificationRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl CardVerificationRequest {
     /// Construct a new request to verify a card.
@@ -38,10 +57,9 @@ impl CardVerificationRequest {
         description: String,
     ) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::CardVerification,
             public_key: public_key.into(),
-            card,
+            card: card.into(),
             card_exp_month: exp_month,
             card_exp_year: exp_year,
             order_id,
@@ -55,7 +73,7 @@ impl CardVerificationRequest {
 
     /// Sets the card CVV/CVV2 code.
     pub fn cvv(mut self, cvv: String) -> Self {
-        self.card_cvv = Some(cvv);
+        self.card_cvv = Some(cvv.into());
         self
     }
 
@@ -76,10 +94,18 @@ impl CardVerificationRequest {
         self.verify_code = Some('Y');
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+
+        Ok(self)
+    }
 }
 
 /// Represents a response to a card verification operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CardVerificationResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -93,22 +119,29 @@ pub struct CardVerificationResponse {
     /// Represents the operation type. Possible value is `cardverification`.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
     /// Represents the authorization code for debit.
     pub authcode_debit: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the phone number used for confirmation via a one-time password.
     pub confirm_phone: Option<String>,
@@ -128,6 +161,7 @@ pub struct CardVerificationResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -150,19 +184,22 @@ pub struct CardVerificationResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
+use crate::common::card::CardCvv;
 use crate::common::enums::{
     Action, Bonus, Currency, Language, MpiEci, PayType, Result, Status, Version,
 };
@@ -9,30 +10,32 @@ use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 /// Represents a request to confirm a payment using CVV.
 #[derive(Debug, Serialize)]
 pub struct CvvRequest {
-    version: Version,
     action: Action,
     public_key: String,
-    card_cvv: String,
+    card_cvv: CardCvv,
     confirm_token: String,
 }
 
-impl LiqPayRequest<CvvResponse, Sha3_256> for CvvRequest {}
+impl LiqPayRequest<CvvResponse, Sha3_256> for CvvRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl CvvRequest {
     /// Constructs a new request to confirm a payment using CVV.
     pub fn new(public_key: impl Into<String>, card_cvv: String, confirm_token: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Confirm,
             public_key: public_key.into(),
-            card_cvv,
+            card_cvv: card_cvv.into(),
             confirm_token,
         }
     }
 }
 
 /// Represents a response to a payment CVV confirmation operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CvvResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -46,14 +49,19 @@ pub struct CvvResponse {
     /// Represents the operation type. Possible value is `cardverification`.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
@@ -67,8 +75,10 @@ pub struct CvvResponse {
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -86,6 +96,7 @@ pub struct CvvResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -108,7 +119,8 @@ pub struct CvvResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the identifier of the transaction in a the issuer bank's system for credit.
     #[serde(rename = "rrn_credit")]
     pub retrieval_reference_number_credit: Option<String>,
@@ -116,17 +128,19 @@ pub struct CvvResponse {
     #[serde(rename = "rrn_debit")]
     pub retrieval_reference_number_debit: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,
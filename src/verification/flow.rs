@@ -0,0 +1,166 @@
+use crate::client::{LiqPayClient, Transport};
+use crate::common::enums::Status;
+use crate::error::LiqPayError;
+use crate::verification::mpi::MpiResponse;
+use crate::verification::otp::{OtpRequest, OtpResponse};
+
+fn missing_confirm_token() -> LiqPayError {
+    LiqPayError::Validation(String::from(
+        "the operation does not have a confirmation token",
+    ))
+}
+
+/// Represents an operation, which awaits a customer's verification via 3DS or a one-time password.
+#[derive(Debug, Clone)]
+pub struct PendingVerification {
+    public_key: String,
+    confirm_token: Option<String>,
+    mpi_req_url: Option<String>,
+    mpi_form: Option<String>,
+}
+
+impl PendingVerification {
+    /// Constructs a pending verification from an MPI response.
+    /// Returns `None` if the response does not require any further verification.
+    pub fn from_mpi_response(
+        public_key: impl Into<String>,
+        response: &MpiResponse,
+    ) -> Option<Self> {
+        let requires_verification =
+            matches!(response.status, Status::Verify3Ds | Status::VerifyOtp)
+                || response.confirm_token.is_some()
+                || response.mpi_req_url.is_some()
+                || response.mpi_form.is_some();
+
+        if !requires_verification {
+            return None;
+        }
+
+        Some(Self {
+            public_key: public_key.into(),
+            confirm_token: response.confirm_token.clone(),
+            mpi_req_url: response.mpi_req_url.clone(),
+            mpi_form: response.mpi_form.clone(),
+        })
+    }
+
+    /// Returns the 3D Secure confirmation page URL, where a customer has to be redirected to.
+    pub fn mpi_req_url(&self) -> Option<&str> {
+        self.mpi_req_url.as_deref()
+    }
+
+    /// Returns the 3D Secure authentication form, which has to be rendered for a customer.
+    pub fn mpi_form(&self) -> Option<&str> {
+        self.mpi_form.as_deref()
+    }
+
+    /// Returns the token to confirm the operation with a one-time password.
+    pub fn confirm_token(&self) -> Option<&str> {
+        self.confirm_token.as_deref()
+    }
+
+    /// Constructs a request to confirm the operation with a one-time password.
+    /// Returns `None` if LiqPay has not issued a confirmation token.
+    pub fn otp_request(&self, otp: String) -> Option<OtpRequest> {
+        let token = self.confirm_token.clone()?;
+
+        Some(OtpRequest::new(self.public_key.clone(), otp, token))
+    }
+
+    /// Confirms the operation with a one-time password entered by a customer.
+    pub async fn confirm_otp<T: Transport>(
+        &self,
+        client: &LiqPayClient<T>,
+        otp: String,
+    ) -> Result<OtpResponse, LiqPayError> {
+        let request = self.otp_request(otp).ok_or_else(missing_confirm_token)?;
+
+        client.send(request).await
+    }
+
+    /// Confirms the operation with a one-time password entered by a customer using a blocking client.
+    #[cfg(feature = "blocking")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+    pub fn confirm_otp_blocking(
+        &self,
+        client: &crate::client::blocking::BlockLiqPayClient,
+        otp: String,
+    ) -> Result<OtpResponse, LiqPayError> {
+        let request = self.otp_request(otp).ok_or_else(missing_confirm_token)?;
+
+        client.send(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::PendingVerification;
+    use crate::client::test_util::MockTransport;
+    use crate::client::{ClientConfig, LiqPayClient};
+    use crate::common::enums::Status;
+    use crate::error::LiqPayError;
+    use crate::verification::mpi::MpiResponse;
+
+    fn mpi_response(response: serde_json::Value) -> MpiResponse {
+        serde_json::from_value(response).unwrap()
+    }
+
+    #[test]
+    fn final_response_does_not_require_verification() {
+        let response = mpi_response(json!({ "status": "success", "result": "ok" }));
+
+        assert!(PendingVerification::from_mpi_response("public_key", &response).is_none());
+    }
+
+    #[test]
+    fn three_ds_page_is_exposed_for_redirect() {
+        let response = mpi_response(json!({
+            "status": "3ds_verify",
+            "mpi_req_url": "https://acs.example.com",
+        }));
+
+        let pending = PendingVerification::from_mpi_response("public_key", &response).unwrap();
+
+        assert_eq!(pending.mpi_req_url(), Some("https://acs.example.com"));
+        assert!(pending.otp_request("1234".to_owned()).is_none());
+    }
+
+    #[tokio::test]
+    async fn otp_is_confirmed_with_issued_token() {
+        let response = mpi_response(json!({ "status": "otp_verify", "confirm_token": "token" }));
+        let pending = PendingVerification::from_mpi_response("public_key", &response).unwrap();
+        let transport =
+            MockTransport::new().respond("confirm", r#"{"result":"ok","status":"success"}"#);
+        let client = LiqPayClient::with_transport("private_key", ClientConfig::new(), transport);
+
+        let confirmation = pending
+            .confirm_otp(&client, "1234".to_owned())
+            .await
+            .unwrap();
+
+        let requests = client.transport().requests();
+        assert_eq!(confirmation.payment.status, Status::Success);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["action"], "confirm");
+        assert_eq!(requests[0]["otp"], "1234");
+        assert_eq!(requests[0]["confirm_token"], "token");
+    }
+
+    #[tokio::test]
+    async fn otp_is_not_sent_without_token() {
+        let response = mpi_response(json!({ "status": "otp_verify" }));
+        let pending = PendingVerification::from_mpi_response("public_key", &response).unwrap();
+        let client =
+            LiqPayClient::with_transport("private_key", ClientConfig::new(), MockTransport::new());
+
+        let error = pending
+            .confirm_otp(&client, "1234".to_owned())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, LiqPayError::Validation(_)));
+        assert!(client.transport().requests().is_empty());
+    }
+}
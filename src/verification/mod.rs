@@ -1,5 +1,6 @@
 pub mod card_verification;
 pub mod cvv;
+pub mod flow;
 pub mod mpi;
 pub mod otp;
 pub mod three_d_secure;
@@ -1,8 +1,20 @@
+use std::fmt::{self, Display, Formatter};
+use std::result::Result as StdResult;
+
+use reqwest::header::{ACCEPT, HeaderMap, HeaderName, USER_AGENT};
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_256;
 
-use crate::common::enums::{Action, Currency, Language, Result, Status, Version};
-use crate::common::traits::{LiqPayRequest, LiqPayResponse};
+use crate::client::ClientConfig;
+use crate::common::amount::{Amount, MonetaryValue};
+use crate::common::card::{CardCvv, CardNumber};
+use crate::common::enums::{Action, Currency, Language, Result, Status};
+use crate::common::traits::{LiqPayRequest, LiqPayResponse, ThreeDsChallenge};
+use crate::common::validation::{
+    DESCRIPTION_MAX_LENGTH, check_max_length, check_not_blank, check_order_id,
+    check_positive_amount,
+};
+use crate::error::LiqPayError;
 
 /// Represents the depth of a browser color.
 #[derive(Debug, Serialize)]
@@ -27,6 +39,25 @@ pub enum BrowserColorDepth {
     FortyEight,
 }
 
+impl BrowserColorDepth {
+    /// Converts the color depth reported by a browser (`screen.colorDepth`) into `BrowserColorDepth`.
+    /// Returns `None` for an unsupported depth.
+    pub fn from_depth(depth: u8) -> Option<Self> {
+        match depth {
+            1 => Some(BrowserColorDepth::One),
+            2 => Some(BrowserColorDepth::Two),
+            4 => Some(BrowserColorDepth::Four),
+            8 => Some(BrowserColorDepth::Eight),
+            15 => Some(BrowserColorDepth::Fifteen),
+            16 => Some(BrowserColorDepth::Sixteen),
+            24 => Some(BrowserColorDepth::TwentyFour),
+            32 => Some(BrowserColorDepth::ThirtyTwo),
+            48 => Some(BrowserColorDepth::FortyEight),
+            _ => None,
+        }
+    }
+}
+
 /// Represents additional 3D Secure information.
 #[derive(Debug, Serialize)]
 pub struct ThreeDsInfo {
@@ -54,6 +85,16 @@ pub struct ThreeDsInfo {
     browser_java_enabled: Option<bool>,
 }
 
+fn check_screen_dimension(field: &str, value: &str) -> StdResult<(), LiqPayError> {
+    match value.trim().parse::<u32>() {
+        Ok(dimension) if dimension > 0 => Ok(()),
+        _ => Err(LiqPayError::Validation(format!(
+            "`{}` must be a positive number of pixels",
+            field
+        ))),
+    }
+}
+
 impl ThreeDsInfo {
     /// Constructs a new instance of additional 3D Secure information.
     pub fn new(
@@ -85,6 +126,61 @@ impl ThreeDsInfo {
         }
     }
 
+    /// Constructs a new instance of additional 3D Secure information from the headers of a customer's
+    /// HTTP request. The `User-Agent` and `Accept` headers are taken from `headers`, the rest of the data
+    /// is collected in the browser. `tz_offset_minutes` is the value of JavaScript's `getTimezoneOffset()`.
+    pub fn from_headers(
+        headers: &HeaderMap,
+        screen_width: u32,
+        screen_height: u32,
+        color_depth: BrowserColorDepth,
+        tz_offset_minutes: i16,
+        language: String,
+        notification_url: String,
+        requestor_url: String,
+    ) -> Self {
+        let header = |name: HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_owned()
+        };
+
+        Self {
+            notification_url,
+            browser_language: language,
+            three_ds_requestor_url: requestor_url,
+            browser_screen_height: screen_height.to_string(),
+            browser_screen_width: screen_width.to_string(),
+            browser_color_depth: color_depth,
+            browser_accept_header: header(ACCEPT),
+            browser_tz: tz_offset_minutes,
+            browser_user_agent: header(USER_AGENT),
+            browser_java_script_enabled: None,
+            browser_java_enabled: None,
+        }
+    }
+
+    /// Validates the information required by 3DS v2 before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    fn validate(&self) -> StdResult<(), LiqPayError> {
+        check_not_blank("notificationURL", &self.notification_url)?;
+        check_not_blank("browserLanguage", &self.browser_language)?;
+        check_not_blank("threeDSRequestorURL", &self.three_ds_requestor_url)?;
+        check_not_blank("browserAcceptHeader", &self.browser_accept_header)?;
+        check_not_blank("browserUserAgent", &self.browser_user_agent)?;
+        check_screen_dimension("browserScreenHeight", &self.browser_screen_height)?;
+        check_screen_dimension("browserScreenWidth", &self.browser_screen_width)?;
+
+        Ok(())
+    }
+
     /// Sets JavaScript execution in a customer's browser to disabled.
     pub fn disable_java_script(mut self) -> Self {
         self.browser_java_enabled = Some(false);
@@ -101,18 +197,17 @@ impl ThreeDsInfo {
 /// Represents a request to verify a card for 3DS support.
 #[derive(Debug, Serialize)]
 pub struct MpiRequest {
-    version: Version,
     action: Action,
     public_key: String,
-    amount: f64,
-    card: String,
+    amount: Amount,
+    card: CardNumber,
     card_exp_month: String,
     card_exp_year: String,
     currency: Currency,
     order_id: String,
     description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    card_cvv: Option<String>,
+    card_cvv: Option<CardCvv>,
     #[serde(skip_serializing_if = "Option::is_none")]
     email: Option<String>,
     ip: Option<String>,
@@ -127,27 +222,42 @@ pub struct MpiRequest {
     three_ds_info: Option<ThreeDsInfo>,
 }
 
-impl LiqPayRequest<MpiResponse, Sha3_256> for MpiRequest {}
+impl LiqPayRequest<MpiResponse, Sha3_256> for MpiRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+
+    fn order_id(&self) -> Option<&str> {
+        Some(&self.order_id)
+    }
+
+    fn apply_defaults(&mut self, config: &ClientConfig) {
+        if self.language.is_none() {
+            self.language = config.language().cloned();
+        }
+    }
+}
 
 impl MpiRequest {
     /// Constructs a new request to verify a card for 3DS support.
-    pub fn new(
+    /// `value` pairs the amount with its currency, e.g. `(100.0, Currency::UAH)`.
+    pub fn with_value(
         public_key: impl Into<String>,
-        amount: f64,
-        currency: Currency,
+        value: impl Into<MonetaryValue>,
         card: String,
         exp_month: String,
         exp_year: String,
         order_id: String,
         description: String,
     ) -> Self {
+        let value = value.into();
+
         Self {
-            version: Version::Seven,
             action: Action::Mpi,
             public_key: public_key.into(),
-            amount,
-            currency,
-            card,
+            amount: value.amount(),
+            currency: value.currency(),
+            card: card.into(),
             card_exp_year: exp_year,
             card_exp_month: exp_month,
             order_id,
@@ -163,9 +273,32 @@ impl MpiRequest {
         }
     }
 
+    /// Constructs a new request to verify a card for 3DS support.
+    #[deprecated(note = "use `with_value`, which pairs the amount with its currency")]
+    pub fn new(
+        public_key: impl Into<String>,
+        amount: impl Into<Amount>,
+        currency: Currency,
+        card: String,
+        exp_month: String,
+        exp_year: String,
+        order_id: String,
+        description: String,
+    ) -> Self {
+        Self::with_value(
+            public_key,
+            MonetaryValue::new(amount, currency),
+            card,
+            exp_month,
+            exp_year,
+            order_id,
+            description,
+        )
+    }
+
     /// Sets the card CVV/CVV2 code.
     pub fn cvv(mut self, cvv: String) -> Self {
-        self.card_cvv = Some(cvv);
+        self.card_cvv = Some(cvv.into());
         self
     }
 
@@ -240,10 +373,24 @@ impl MpiRequest {
         self.three_ds_info = Some(info);
         self
     }
+
+    /// Validates the request before it is sent.
+    pub fn build(self) -> StdResult<Self, LiqPayError> {
+        check_order_id(&self.order_id)?;
+        check_max_length("description", &self.description, DESCRIPTION_MAX_LENGTH)?;
+
+        if let Some(info) = &self.three_ds_info {
+            info.validate()?;
+        }
+
+        check_positive_amount("amount", self.amount.round(self.currency))?;
+
+        Ok(self)
+    }
 }
 
 /// Represents an MPI status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum MpiStatus {
     /// In 3DS Version 1.0 represents the support for 3D Secure,
     /// in Version 2.0 - no additional verification required.
@@ -259,8 +406,38 @@ pub enum MpiStatus {
     U,
 }
 
+impl MpiStatus {
+    /// Indicates whether the cardholder is authenticated without additional verification,
+    /// i.e. the status is `Y` or `A` in 3DS Version 2.0.
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self, MpiStatus::Y | MpiStatus::A)
+    }
+
+    /// Indicates whether the cardholder must pass a challenge, i.e. the status is `C` in 3DS Version 2.0.
+    pub fn requires_challenge(&self) -> bool {
+        matches!(self, MpiStatus::C)
+    }
+
+    /// Returns the meaning of the status in 3DS Version 2.0.
+    pub fn description(&self) -> &'static str {
+        match self {
+            MpiStatus::Y => "authenticated",
+            MpiStatus::A => "authentication attempted",
+            MpiStatus::C => "challenge required",
+            MpiStatus::N => "not authenticated",
+            MpiStatus::U => "authentication could not be performed",
+        }
+    }
+}
+
+impl Display for MpiStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} ({})", self, self.description())
+    }
+}
+
 /// Represents the response to a 3D Secure card verification operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct MpiResponse {
     /// Represents the status of the request.
     /// Possible values are `error` - incorrect data, `failure` - payment failed.
@@ -270,7 +447,8 @@ pub struct MpiResponse {
     /// Represents a required parameter for ACS authentication.
     pub mpi_req_md: Option<String>,
     /// Represents a required parameter for ACS authentication.
-    pub mpi_re1q_pareq: Option<String>,
+    #[serde(flatten, with = "crate::common::de::mpi_req_pareq")]
+    pub mpi_req_pareq: Option<String>,
     /// Represents a #D Secure confirmation page URL.
     pub mpi_req_url: Option<String>,
     /// Represents a status of 3D Secure verification.
@@ -281,6 +459,8 @@ pub struct MpiResponse {
     pub mpi_form: Option<String>,
     /// Represents the required parameter for authentication for N and Y statuses.
     pub mpi_cres: Option<String>,
+    /// Represents the token to confirm the operation with a one-time password.
+    pub confirm_token: Option<String>,
     /// Holds an error code.
     #[serde(rename = "err_code")]
     pub error_code: Option<String>,
@@ -290,3 +470,210 @@ pub struct MpiResponse {
 }
 
 impl LiqPayResponse for MpiResponse {}
+
+impl ThreeDsChallenge for MpiResponse {
+    fn challenge_url(&self) -> Option<&str> {
+        self.mpi_req_url.as_deref()
+    }
+}
+
+impl MpiResponse {
+    /// Returns the `PaReq`, `MD` and URL parameters for the 3D Secure 1.0 redirect to the ACS.
+    /// Returns `None` if any of them is absent.
+    pub fn acs_params(&self) -> Option<(&str, &str, &str)> {
+        Some((
+            self.mpi_req_pareq.as_deref()?,
+            self.mpi_req_md.as_deref()?,
+            self.mpi_req_url.as_deref()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{ACCEPT, HeaderMap, HeaderValue, USER_AGENT};
+    use serde_json::json;
+
+    use super::{BrowserColorDepth, MpiResponse, MpiStatus, ThreeDsInfo};
+    use crate::common::traits::ThreeDsChallenge;
+    use crate::error::LiqPayError;
+
+    #[test]
+    fn browser_color_depth_is_converted_from_reported_depth() {
+        for depth in [1, 2, 4, 8, 15, 16, 24, 32, 48] {
+            let color_depth = BrowserColorDepth::from_depth(depth).unwrap();
+
+            assert_eq!(
+                serde_json::to_value(color_depth).unwrap(),
+                json!(depth.to_string())
+            );
+        }
+
+        assert!(BrowserColorDepth::from_depth(0).is_none());
+        assert!(BrowserColorDepth::from_depth(30).is_none());
+    }
+
+    fn three_ds_info(headers: &HeaderMap) -> ThreeDsInfo {
+        ThreeDsInfo::from_headers(
+            headers,
+            1920,
+            1080,
+            BrowserColorDepth::TwentyFour,
+            -120,
+            "uk-UA".to_owned(),
+            "https://shop.example.com/3ds".to_owned(),
+            "https://shop.example.com".to_owned(),
+        )
+    }
+
+    #[test]
+    fn three_ds_info_is_filled_from_request_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("Mozilla/5.0"));
+        headers.insert(ACCEPT, HeaderValue::from_static("text/html"));
+
+        let info = three_ds_info(&headers).build().unwrap();
+
+        assert_eq!(
+            serde_json::to_value(info).unwrap(),
+            json!({
+                "notificationURL": "https://shop.example.com/3ds",
+                "browserLanguage": "uk-UA",
+                "threeDSRequestorURL": "https://shop.example.com",
+                "browserScreenHeight": "1080",
+                "browserColorDepth": "24",
+                "browserScreenWidth": "1920",
+                "browserAcceptHeader": "text/html",
+                "browserTZ": -120,
+                "browserUserAgent": "Mozilla/5.0",
+                "browserJavascriptEnabled": null,
+                "browserJavaEnabled": null,
+            })
+        );
+    }
+
+    #[test]
+    fn three_ds_info_without_user_agent_is_rejected() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("text/html"));
+
+        assert!(three_ds_info(&headers).build().is_err());
+    }
+
+    fn browser_info(width: &str, user_agent: &str) -> ThreeDsInfo {
+        ThreeDsInfo::new(
+            "https://shop.example.com/3ds".to_owned(),
+            "uk-UA".to_owned(),
+            "https://shop.example.com".to_owned(),
+            "1080".to_owned(),
+            width.to_owned(),
+            BrowserColorDepth::TwentyFour,
+            "text/html".to_owned(),
+            2,
+            user_agent.to_owned(),
+        )
+    }
+
+    #[test]
+    fn blank_user_agent_is_rejected() {
+        assert!(matches!(
+            browser_info("1920", " ").build(),
+            Err(LiqPayError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn zero_or_non_numeric_screen_width_is_rejected() {
+        for width in ["0", "wide"] {
+            assert!(
+                matches!(
+                    browser_info(width, "Mozilla/5.0").build(),
+                    Err(LiqPayError::Validation(_))
+                ),
+                "{width}"
+            );
+        }
+    }
+
+    #[test]
+    fn complete_browser_info_is_accepted() {
+        assert!(browser_info("1920", "Mozilla/5.0").build().is_ok());
+    }
+
+    #[test]
+    fn acs_params_are_read_from_mpi_response() {
+        let response: MpiResponse = serde_json::from_value(json!({
+            "status": "3ds_verify",
+            "result": "ok",
+            "mpi_req_md": "md",
+            "mpi_req_pareq": "pareq",
+            "mpi_req_url": "https://acs.example.com",
+        }))
+        .unwrap();
+        let misspelled: MpiResponse = serde_json::from_value(json!({
+            "status": "3ds_verify",
+            "mpi_req_md": "md",
+            "mpi_re1q_pareq": "pareq",
+            "mpi_req_url": "https://acs.example.com",
+        }))
+        .unwrap();
+
+        let acs_params = Some(("pareq", "md", "https://acs.example.com"));
+        assert_eq!(response.acs_params(), acs_params);
+        assert_eq!(misspelled.acs_params(), acs_params);
+        assert_eq!(
+            serde_json::to_value(&misspelled).unwrap()["mpi_req_pareq"],
+            "pareq"
+        );
+    }
+
+    #[test]
+    fn acs_params_are_absent_without_pareq() {
+        let response: MpiResponse = serde_json::from_value(json!({
+            "status": "3ds_verify",
+            "mpi_req_md": "md",
+            "mpi_req_url": "https://acs.example.com",
+        }))
+        .unwrap();
+
+        assert_eq!(response.acs_params(), None);
+    }
+
+    #[test]
+    fn challenge_url_is_redirect_only_when_present() {
+        let redirected: MpiResponse = serde_json::from_value(json!({
+            "result": "ok",
+            "status": "3ds_verify",
+            "mpi_req_url": "https://acs.example.com",
+        }))
+        .unwrap();
+        let completed: MpiResponse =
+            serde_json::from_value(json!({ "result": "ok", "status": "success" })).unwrap();
+
+        assert_eq!(redirected.challenge_url(), Some("https://acs.example.com"));
+        assert_eq!(completed.challenge_url(), None);
+    }
+
+    #[test]
+    fn mpi_status_is_classified_under_3ds_v2() {
+        // (status, is_authenticated, requires_challenge, display)
+        let classification = [
+            (MpiStatus::Y, true, false, "Y (authenticated)"),
+            (MpiStatus::A, true, false, "A (authentication attempted)"),
+            (MpiStatus::C, false, true, "C (challenge required)"),
+            (MpiStatus::N, false, false, "N (not authenticated)"),
+            (
+                MpiStatus::U,
+                false,
+                false,
+                "U (authentication could not be performed)",
+            ),
+        ];
+
+        for (status, authenticated, challenge, display) in classification {
+            assert_eq!(status.is_authenticated(), authenticated, "{status}");
+            assert_eq!(status.requires_challenge(), challenge, "{status}");
+            assert_eq!(status.to_string(), display);
+        }
+    }
+}
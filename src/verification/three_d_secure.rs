@@ -9,20 +9,22 @@ use crate::common::traits::{LiqPayRequest, LiqPayResponse};
 /// Represents a request to confirm an operation via 3D Secure.
 #[derive(Debug, Serialize)]
 pub struct ThreeDSecureRequest {
-    version: Version,
     action: Action,
     public_key: String,
     #[serde(rename = "confirm_token")]
     token: String,
 }
 
-impl LiqPayRequest<ThreeDSecureResponse, Sha3_256> for ThreeDSecureRequest {}
+impl LiqPayRequest<ThreeDSecureResponse, Sha3_256> for ThreeDSecureRequest {
+    fn action(&self) -> Option<Action> {
+        Some(self.action.clone())
+    }
+}
 
 impl ThreeDSecureRequest {
     /// Constructs a new request to confirm an operation via 3D Secure.
     pub fn new(public_key: impl Into<String>, token: String) -> Self {
         Self {
-            version: Version::Seven,
             action: Action::Confirm,
             public_key: public_key.into(),
             token,
@@ -31,7 +33,7 @@ impl ThreeDSecureRequest {
 }
 
 /// Represents the response to a 3D Secure confirmation operation.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ThreeDSecureResponse {
     /// Represents the result of the request. Can be either `ok` or `error`.
     pub result: Result,
@@ -48,14 +50,19 @@ pub struct ThreeDSecureResponse {
     /// `auth` - card preauthentication, `regular` - regular payment, `paysplit` - split payment.
     pub action: Option<Action>,
     /// Represents the agent commission.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub agent_commission: Option<f64>,
     /// Represents the payment amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount: Option<f64>,
     /// Represents the payer bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_bonus: Option<f64>,
     /// Represents the payment credit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_credit: Option<f64>,
     /// Represents the payment debit amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub amount_debit: Option<f64>,
     /// Represents the authorization code for credit.
     pub authcode_credit: Option<String>,
@@ -69,8 +76,10 @@ pub struct ThreeDSecureResponse {
     /// Represents the sender's card token.
     pub card_token: Option<String>,
     /// Represents the commission charged to credit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_credit: Option<f64>,
     /// Represents the commission charged to debit.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub commission_debit: Option<f64>,
     /// Represents the payment creation date.
     #[serde(rename = "create_date")]
@@ -88,6 +97,7 @@ pub struct ThreeDSecureResponse {
     /// Represents the IP address of a sender.
     pub ip: Option<String>,
     /// Indicates whether a transaction passed with 3DS.
+    #[serde(default, deserialize_with = "crate::common::de::optional_bool")]
     pub is_3ds: Option<bool>,
     /// Represents the language of the payment.
     pub language: Option<Language>,
@@ -111,7 +121,8 @@ pub struct ThreeDSecureResponse {
     /// Represents the public key of the shop.
     pub public_key: Option<String>,
     /// Represents the receiver's commission.
-    pub receiver_commission: Option<f32>,
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
+    pub receiver_commission: Option<f64>,
     /// Represents the identifier of the transaction in a the issuer bank's system for credit.
     #[serde(rename = "rrn_credit")]
     pub retrieval_reference_number_credit: Option<String>,
@@ -119,17 +130,19 @@ pub struct ThreeDSecureResponse {
     #[serde(rename = "rrn_debit")]
     pub retrieval_reference_number_debit: Option<String>,
     /// Represents the sender's bonus amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_bonus: Option<f64>,
     /// Represents the sender's bank.
     pub sender_card_bank: Option<String>,
     /// Represents the sender's card country code in the ISO-3166 format.
     pub sender_card_country: Option<u16>,
     /// Represents the masked sender card number.
-    #[serde(rename = "sender_card_mask2")]
+    #[serde(flatten, with = "crate::common::de::sender_card_mask")]
     pub sender_card_mask: Option<String>,
     /// Represents the sender's card type - either VISA or MasterCard.
     pub sender_card_type: Option<String>,
     /// Represents the sender's commission amount.
+    #[serde(default, deserialize_with = "crate::common::de::optional_f64")]
     pub sender_commission: Option<f64>,
     /// Represents the sender's first name.
     pub sender_first_name: Option<String>,